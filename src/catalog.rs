@@ -1,5 +1,10 @@
-use crate::value::*;
+use crate::{error::Error, minor, sat, value::*};
 use pracstro::{coord, time};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Objects by their lowercase name
+pub type Catalog = HashMap<String, CelObj>;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Star {
@@ -8,18 +13,149 @@ pub struct Star {
     pub pi: time::Angle,
     pub pm_ra: time::Angle,
     pub pm_dec: time::Angle,
+    /// Radial velocity in km/s, positive receding
+    pub rv: f64,
+}
+
+impl Star {
+    /// Position relative to the sun in AU, in the J2000 equatorial frame
+    ///
+    /// Moves the star from its J2000 position along a straight line through space,
+    /// using its proper motion, parallax, and radial velocity.
+    pub fn locationcart(&self, d: time::Date) -> (f64, f64, f64) {
+        const KMS_TO_AUYR: f64 = 0.210_945_021;
+        let (ra, de) = self.loc_j2k.equatorial();
+        // Stars without a parallax are put far enough away that it makes no difference
+        let dist = match self.pi.to_latitude().radians() {
+            p if p > 0.0 => 1.0 / p,
+            _ => 1e12,
+        };
+        let (vt_ra, vt_de) = (
+            self.pm_ra.to_latitude().radians() * dist,
+            self.pm_dec.to_latitude().radians() * dist,
+        );
+        let vr = self.rv * KMS_TO_AUYR;
+        let t = (d.julian() - time::J2000.julian()) / 365.25;
+
+        // Unit vectors towards the star, east, and north
+        let r = (de.cos() * ra.cos(), de.cos() * ra.sin(), de.sin());
+        let p = (-ra.sin(), ra.cos(), 0.0);
+        let q = (-de.sin() * ra.cos(), -de.sin() * ra.sin(), de.cos());
+
+        (
+            r.0 * (dist + vr * t) + (p.0 * vt_ra + q.0 * vt_de) * t,
+            r.1 * (dist + vr * t) + (p.1 * vt_ra + q.1 * vt_de) * t,
+            r.2 * (dist + vr * t) + (p.2 * vt_ra + q.2 * vt_de) * t,
+        )
+    }
+
+    /// Equatorial coordinates at the date, with space motion and precession applied
+    pub fn location(&self, d: time::Date) -> coord::Coord {
+        let (x, y, z) = self.locationcart(d);
+        coord::Coord::from_cartesian(x, y, z).precess(time::J2000, d)
+    }
+
+    /// Distance from the sun in AU
+    pub fn distance(&self, d: time::Date) -> f64 {
+        let (x, y, z) = self.locationcart(d);
+        (x * x + y * y + z * z).sqrt()
+    }
+}
+
+/// The kinds of deep sky objects
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DsoKind {
+    Galaxy,
+    OpenCluster,
+    GlobularCluster,
+    PlanetaryNebula,
+    Nebula,
+    /// An open cluster with the nebula it formed from
+    ClusterNebula,
+    SupernovaRemnant,
+    DarkNebula,
+    StarCloud,
+    Asterism,
+    DoubleStar,
+}
+impl DsoKind {
+    /// From the abbreviations used in `deepsky.csv`
+    fn from_code(s: &str) -> Option<Self> {
+        match s {
+            "gx" => Some(DsoKind::Galaxy),
+            "oc" => Some(DsoKind::OpenCluster),
+            "gc" => Some(DsoKind::GlobularCluster),
+            "pn" => Some(DsoKind::PlanetaryNebula),
+            "neb" => Some(DsoKind::Nebula),
+            "cln" => Some(DsoKind::ClusterNebula),
+            "snr" => Some(DsoKind::SupernovaRemnant),
+            "dn" => Some(DsoKind::DarkNebula),
+            "sc" => Some(DsoKind::StarCloud),
+            "ast" => Some(DsoKind::Asterism),
+            "dbl" => Some(DsoKind::DoubleStar),
+            _ => None,
+        }
+    }
+}
+impl fmt::Display for DsoKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                DsoKind::Galaxy => "Galaxy",
+                DsoKind::OpenCluster => "Open Cluster",
+                DsoKind::GlobularCluster => "Globular Cluster",
+                DsoKind::PlanetaryNebula => "Planetary Nebula",
+                DsoKind::Nebula => "Nebula",
+                DsoKind::ClusterNebula => "Cluster with Nebula",
+                DsoKind::SupernovaRemnant => "Supernova Remnant",
+                DsoKind::DarkNebula => "Dark Nebula",
+                DsoKind::StarCloud => "Star Cloud",
+                DsoKind::Asterism => "Asterism",
+                DsoKind::DoubleStar => "Double Star",
+            }
+        )
+    }
+}
+
+/// A galaxy, cluster, or nebula
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeepSky {
+    pub loc_j2k: coord::Coord,
+    pub kind: DsoKind,
+    /// Integrated visual magnitude, dark nebulae don't have one
+    pub mag: Option<f64>,
+    /// Major and minor axes, in arcminutes
+    pub size: (f64, f64),
+}
+
+impl DeepSky {
+    /// Equatorial coordinates at the date, these objects are too far away to move
+    pub fn location(&self, d: time::Date) -> coord::Coord {
+        self.loc_j2k.precess(time::J2000, d)
+    }
+
+    /// Average surface brightness in magnitudes per square arcminute, over an ellipse the size of the object
+    pub fn surface_brightness(&self) -> Option<f64> {
+        let area = std::f64::consts::FRAC_PI_4 * self.size.0 * self.size.1;
+        self.mag.map(|m| m + 2.5 * area.log10())
+    }
 }
 
 /// Creates the catalog as a hash table
 ///
 /// This operation takes about 500 µs on my machine
-pub fn read() -> std::collections::HashMap<&'static str, CelObj> {
+pub fn read() -> Catalog {
+    use crate::moons::PlanetMoon;
     use pracstro::sol;
 
-    let mut cat = std::collections::HashMap::from([
+    let mut cat: Catalog = HashMap::from([
         ("sun", CelObj::Sun),
+        ("site", CelObj::Site),
         ("mercury", CelObj::Planet(sol::MERCURY)),
         ("venus", CelObj::Planet(sol::VENUS)),
+        ("earth", CelObj::Planet(sol::EARTH)),
         ("moon", CelObj::Moon),
         ("mars", CelObj::Planet(sol::MARS)),
         ("jupiter", CelObj::Planet(sol::JUPITER)),
@@ -27,30 +163,739 @@ pub fn read() -> std::collections::HashMap<&'static str, CelObj> {
         ("uranus", CelObj::Planet(sol::URANUS)),
         ("neptune", CelObj::Planet(sol::NEPTUNE)),
         ("pluto", CelObj::Planet(sol::PLUTO)),
-    ]);
+        ("io", CelObj::PlanetMoon(PlanetMoon::Io)),
+        ("europa", CelObj::PlanetMoon(PlanetMoon::Europa)),
+        ("ganymede", CelObj::PlanetMoon(PlanetMoon::Ganymede)),
+        ("callisto", CelObj::PlanetMoon(PlanetMoon::Callisto)),
+        ("mimas", CelObj::PlanetMoon(PlanetMoon::Mimas)),
+        ("enceladus", CelObj::PlanetMoon(PlanetMoon::Enceladus)),
+        ("tethys", CelObj::PlanetMoon(PlanetMoon::Tethys)),
+        ("dione", CelObj::PlanetMoon(PlanetMoon::Dione)),
+        ("rhea", CelObj::PlanetMoon(PlanetMoon::Rhea)),
+        ("titan", CelObj::PlanetMoon(PlanetMoon::Titan)),
+    ])
+    .into_iter()
+    .map(|(n, o)| (n.to_string(), o))
+    .collect();
+
+    for (n, s) in
+        stars_csv("stars.csv", include_str!("dat/stars.csv")).expect("Bad built in star catalog")
+    {
+        cat.insert(n, s);
+    }
 
-    include_str!("dat/stars.csv")
+    include_str!("dat/deepsky.csv")
         .lines()
         .skip(1)
-        .map(|star| {
-            let p: Vec<&str> = star.split(',').collect();
-            (
-                p[0],
-                CelObj::Star(Star {
-                    loc_j2k: coord::Coord::from_equatorial(
-                        time::Angle::from_degrees(p[1].parse().unwrap()),
-                        time::Angle::from_degrees(p[2].parse().unwrap()),
-                    ),
-                    mag: p[3].parse().unwrap(),
-                    pi: time::Angle::from_degrees(p[4].parse::<f64>().unwrap() / 3_600_000.0),
-                    pm_ra: time::Angle::from_degrees(p[5].parse::<f64>().unwrap() / 3_600_000.0),
-                    pm_dec: time::Angle::from_degrees(p[6].parse::<f64>().unwrap() / 3_600_000.0),
-                }),
-            )
-        })
-        .for_each(|(n, s)| {
-            cat.insert(n, s);
+        .for_each(|dso| {
+            let p: Vec<&str> = dso.split(',').collect();
+            let obj = CelObj::DeepSky(DeepSky {
+                loc_j2k: coord::Coord::from_equatorial(
+                    time::Angle::from_degrees(p[1].parse().unwrap()),
+                    time::Angle::from_degrees(p[2].parse().unwrap()),
+                ),
+                kind: DsoKind::from_code(p[3]).unwrap(),
+                mag: p[4].parse().ok(),
+                size: (p[5].parse().unwrap(), p[6].parse().unwrap()),
+            });
+            // Messier, Caldwell, NGC/IC numbers, and common names, stars keep their names
+            for n in p[0].split(';') {
+                cat.entry(n.to_string()).or_insert(obj.clone());
+            }
         });
 
     cat
 }
+
+/// Columns `a` to `b` of a fixed width line, counting from 1 and inclusive, as in the MPC's format descriptions
+fn columns<'a>(
+    path: &str,
+    n: usize,
+    line: &'a str,
+    (a, b): (usize, usize),
+) -> Result<&'a str, Error> {
+    line.get(a - 1..b.min(line.len()))
+        .map(str::trim)
+        .ok_or(Error::BadCatalog(path.to_string(), n, a, "Line too short"))
+}
+
+fn number(path: &str, n: usize, line: &str, c: (usize, usize)) -> Result<f64, Error> {
+    columns(path, n, line, c)?
+        .parse()
+        .ok()
+        .ok_or(Error::BadCatalog(
+            path.to_string(),
+            n,
+            c.0,
+            "Expected a number",
+        ))
+}
+
+/// Dates packed into 5 characters, like `K24AH` for 2024-10-17
+fn packed_date(path: &str, n: usize, line: &str, c: (usize, usize)) -> Result<time::Date, Error> {
+    fn digit(c: char) -> Option<u8> {
+        match c {
+            '0'..='9' => Some(c as u8 - b'0'),
+            'A'..='V' => Some(c as u8 - b'A' + 10),
+            _ => None,
+        }
+    }
+    let bad = Error::BadCatalog(path.to_string(), n, c.0, "Bad packed date");
+    let s: Vec<char> = columns(path, n, line, c)?.chars().collect();
+    if s.len() != 5 {
+        return Err(bad);
+    }
+    let century = match s[0] {
+        'I' => 1800,
+        'J' => 1900,
+        'K' => 2000,
+        _ => return Err(bad),
+    };
+    let year = s[1..3]
+        .iter()
+        .collect::<String>()
+        .parse::<i64>()
+        .ok()
+        .ok_or(bad.clone())?;
+    let (month, day) = (digit(s[3]).ok_or(bad.clone())?, digit(s[4]).ok_or(bad)?);
+    Ok(time::Date::from_calendar(
+        century + year,
+        month,
+        day,
+        time::Angle::default(),
+    ))
+}
+
+/// Splits a line on `sep`, giving each field trimmed along with the column it starts at, counting from 1
+fn fields(line: &str, sep: char) -> Vec<(usize, &str)> {
+    let mut col = 1;
+    line.split(sep)
+        .map(|f| {
+            let field = (col, f.trim());
+            col += f.chars().count() + 1;
+            field
+        })
+        .collect()
+}
+
+/// Field `i` of a line split with [`fields`] as a number, or `None` if it's empty
+fn field_number(
+    path: &str,
+    n: usize,
+    line: &str,
+    f: &[(usize, &str)],
+    i: usize,
+) -> Result<Option<f64>, Error> {
+    match f.get(i) {
+        None => Err(Error::BadCatalog(
+            path.to_string(),
+            n,
+            line.chars().count() + 1,
+            "Missing column",
+        )),
+        Some((_, "")) => Ok(None),
+        Some((c, x)) => x.parse().map(Some).ok().ok_or(Error::BadCatalog(
+            path.to_string(),
+            n,
+            *c,
+            "Expected a number",
+        )),
+    }
+}
+
+/// Like [`field_number`], for fields that can't be empty
+fn required_number(
+    path: &str,
+    n: usize,
+    line: &str,
+    f: &[(usize, &str)],
+    i: usize,
+) -> Result<f64, Error> {
+    field_number(path, n, line, f, i)?.ok_or(Error::BadCatalog(
+        path.to_string(),
+        n,
+        f[i].0,
+        "Expected a number",
+    ))
+}
+
+/// A star from its J2000 position in degrees, and parallax and proper motion in milliarcseconds
+fn star(ra: f64, de: f64, mag: f64, pi: f64, pm_ra: f64, pm_dec: f64, rv: f64) -> CelObj {
+    CelObj::Star(Star {
+        loc_j2k: coord::Coord::from_equatorial(
+            time::Angle::from_degrees(ra),
+            time::Angle::from_degrees(de),
+        ),
+        mag,
+        pi: time::Angle::from_degrees(pi / 3_600_000.0),
+        pm_ra: time::Angle::from_degrees(pm_ra / 3_600_000.0),
+        pm_dec: time::Angle::from_degrees(pm_dec / 3_600_000.0),
+        rv,
+    })
+}
+
+/// Stars in the same CSV format as the built in catalog
+///
+/// Name, RA and Dec in degrees, magnitude, parallax and proper motion in mas, and optionally radial velocity in km/s.
+/// The first line is a header, and blank lines or lines starting with `#` are ignored.
+fn stars_csv(path: &str, text: &str) -> Result<Vec<(String, CelObj)>, Error> {
+    let mut found = vec![];
+    for (i, line) in text.lines().enumerate().skip(1) {
+        let n = i + 1;
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let f = fields(line, ',');
+        if f[0].1.is_empty() {
+            return Err(Error::BadCatalog(path.to_string(), n, 1, "Missing name"));
+        }
+        let num = |i| required_number(path, n, line, &f, i);
+        found.push((
+            f[0].1.to_lowercase(),
+            star(
+                num(1)?,
+                num(2)?,
+                num(3)?,
+                num(4)?,
+                num(5)?,
+                num(6)?,
+                // Optional column, most of the built in catalog doesn't have it
+                match f.get(7) {
+                    Some(_) => field_number(path, n, line, &f, 7)?.unwrap_or(0.0),
+                    None => 0.0,
+                },
+            ),
+        ));
+    }
+    Ok(found)
+}
+
+/// Stars from the main Hipparcos catalog (`hip_main.dat`), named like `hip32349`
+///
+/// Stars without a position in the catalog are left out.
+fn stars_hipparcos(path: &str, text: &str) -> Result<Vec<(String, CelObj)>, Error> {
+    let mut found = vec![];
+    for (i, line) in text.lines().enumerate() {
+        let n = i + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let f = fields(line, '|');
+        let num = |i| field_number(path, n, line, &f, i);
+        let hip = required_number(path, n, line, &f, 1)?;
+        let (Some(ra), Some(de)) = (num(8)?, num(9)?) else {
+            continue;
+        };
+        found.push((
+            format!("hip{hip}"),
+            star(
+                ra,
+                de,
+                required_number(path, n, line, &f, 5)?,
+                num(11)?.unwrap_or(0.0),
+                num(12)?.unwrap_or(0.0),
+                num(13)?.unwrap_or(0.0),
+                0.0,
+            ),
+        ));
+    }
+    Ok(found)
+}
+
+/// Stars from the Yale Bright Star Catalog, 5th edition (`catalog` from the CDS)
+///
+/// Stars are named by their HR number (`hr2491`), and their Bayer (`alp cma`) and Flamsteed (`9 cma`)
+/// designations when they have them. Objects without a position, like novae, are left out.
+fn stars_bsc(path: &str, text: &str) -> Result<Vec<(String, CelObj)>, Error> {
+    let mut found = vec![];
+    for (i, line) in text.lines().enumerate() {
+        let n = i + 1;
+        if line.trim().is_empty() || columns(path, n, line, (76, 77))?.is_empty() {
+            continue;
+        }
+        let num = |c| number(path, n, line, c);
+        let optional = |c| match columns(path, n, line, c)? {
+            "" => Ok(0.0),
+            _ => num(c),
+        };
+        let ra = (num((76, 77))? + num((78, 79))? / 60.0 + num((80, 83))? / 3600.0) * 15.0;
+        let de = num((85, 86))? + num((87, 88))? / 60.0 + num((89, 90))? / 3600.0;
+        let de = match columns(path, n, line, (84, 84))? {
+            "-" => -de,
+            "+" => de,
+            _ => {
+                return Err(Error::BadCatalog(
+                    path.to_string(),
+                    n,
+                    84,
+                    "Expected a sign",
+                ))
+            }
+        };
+        let obj = star(
+            ra,
+            de,
+            num((103, 107))?,
+            // Given in arcseconds, and dynamic parallaxes are as good as any here
+            optional((162, 166))? * 1000.0,
+            optional((149, 154))? * 1000.0,
+            optional((155, 160))? * 1000.0,
+            optional((167, 170))?,
+        );
+
+        let hr = columns(path, n, line, (1, 4))?;
+        let flamsteed = columns(path, n, line, (5, 7))?;
+        let bayer = columns(path, n, line, (8, 11))?;
+        let constellation = columns(path, n, line, (12, 14))?.to_lowercase();
+        found.push((format!("hr{hr}"), obj.clone()));
+        if !bayer.is_empty() {
+            found.push((
+                format!("{} {constellation}", bayer.to_lowercase()),
+                obj.clone(),
+            ));
+        }
+        if !flamsteed.is_empty() {
+            found.push((format!("{flamsteed} {constellation}"), obj));
+        }
+    }
+    Ok(found)
+}
+
+/// Reads a star catalog from a file
+///
+/// CSV files like the built in catalog (starting with a `name,` header), the Hipparcos main catalog,
+/// and the Yale Bright Star Catalog are understood, and told apart by their contents.
+pub fn read_stars(path: &str) -> Result<Vec<(String, CelObj)>, Error> {
+    let text = std::fs::read_to_string(path).map_err(|e| Error::Io(format!("{path}: {e}")))?;
+    let first = text.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+    if first.to_lowercase().starts_with("name,") {
+        stars_csv(path, &text)
+    } else if first.starts_with("H|") {
+        stars_hipparcos(path, &text)
+    } else {
+        stars_bsc(path, &text)
+    }
+}
+
+/// Star catalogs that are always loaded, from `deskephem/catalogs` in the user's config directory
+///
+/// This follows `$XDG_CONFIG_HOME`, falling back to `~/.config`. Files are given in order of their name.
+pub fn config_catalogs() -> Vec<String> {
+    let Some(base) = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|x| !x.is_empty())
+        .map(std::path::PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| std::path::Path::new(&h).join(".config")))
+    else {
+        return vec![];
+    };
+    let Ok(dir) = std::fs::read_dir(base.join("deskephem").join("catalogs")) else {
+        return vec![];
+    };
+    let mut files: Vec<String> = dir
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file())
+        .map(|p| p.to_string_lossy().into_owned())
+        .collect();
+    files.sort();
+    files
+}
+
+/// Adds objects loaded from a file to the catalog, giving the names that were already taken
+///
+/// The first object with a name keeps it, so built in objects can't be replaced.
+pub fn merge(cat: &mut Catalog, found: Vec<(String, CelObj)>) -> Vec<String> {
+    let mut taken = vec![];
+    for (name, obj) in found {
+        match cat.entry(name) {
+            std::collections::hash_map::Entry::Occupied(e) => taken.push(e.key().clone()),
+            std::collections::hash_map::Entry::Vacant(e) => {
+                e.insert(obj);
+            }
+        }
+    }
+    taken
+}
+
+/// Reads minor planets and comets from a file of orbital elements
+///
+/// Both the MPC's format for minor planets (as in `MPCORB.DAT`) and for comets (as in `CometEls.txt`)
+/// are understood, and can be mixed in one file. Minor planets without an absolute magnitude are left out.
+pub fn read_elements(path: &str) -> Result<Vec<(String, CelObj)>, Error> {
+    let text = std::fs::read_to_string(path).map_err(|e| Error::Io(format!("{path}: {e}")))?;
+    // MPCORB.DAT starts with a description, ending in a line of dashes
+    let start = text
+        .lines()
+        .position(|l| l.starts_with("-----"))
+        .map_or(0, |x| x + 1);
+
+    let mut found = vec![];
+    for (i, line) in text.lines().enumerate().skip(start) {
+        let n = i + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        // Comets have the year of perihelion where minor planets have their slope parameter
+        let is_comet = line
+            .get(14..18)
+            .is_some_and(|x| x.chars().all(|c| c.is_ascii_digit()));
+        if is_comet {
+            found.extend(comet(path, n, line)?);
+        } else if let Some(mp) = minor_planet(path, n, line)? {
+            found.push(mp);
+        }
+    }
+    Ok(found)
+}
+
+fn minor_planet(path: &str, n: usize, line: &str) -> Result<Option<(String, CelObj)>, Error> {
+    let deg = |c| Ok::<_, Error>(time::Angle::from_degrees(number(path, n, line, c)?));
+    if columns(path, n, line, (9, 13))?.is_empty() {
+        return Ok(None);
+    }
+    let g = match columns(path, n, line, (15, 19))? {
+        "" => 0.15,
+        _ => number(path, n, line, (15, 19))?,
+    };
+    let body = minor::MinorBody {
+        q: number(path, n, line, (93, 103))? * (1.0 - number(path, n, line, (71, 79))?),
+        e: number(path, n, line, (71, 79))?,
+        i: deg((60, 68))?,
+        node: deg((49, 57))?,
+        peri: deg((38, 46))?,
+        tp: minor::perihelion_time(
+            number(path, n, line, (93, 103))?,
+            deg((27, 35))?,
+            packed_date(path, n, line, (21, 25))?,
+        ),
+        mag: minor::MagLaw::HG(number(path, n, line, (9, 13))?, g),
+    };
+    // Numbered minor planets are written like "(1) Ceres"
+    let name = columns(path, n, line, (167, 194))?;
+    let name = match name.split_once(") ") {
+        Some((_, x)) if name.starts_with('(') => x,
+        _ => name,
+    };
+    if name.is_empty() {
+        return Err(Error::BadCatalog(path.to_string(), n, 167, "Missing name"));
+    }
+    Ok(Some((name.to_lowercase(), CelObj::Minor(body))))
+}
+
+/// A comet, along with its short names for numbered periodic comets (`1p` and `halley` for `1P/Halley`)
+fn comet(path: &str, n: usize, line: &str) -> Result<Vec<(String, CelObj)>, Error> {
+    let deg = |c| Ok::<_, Error>(time::Angle::from_degrees(number(path, n, line, c)?));
+    let day = number(path, n, line, (23, 29))?;
+    let tp = time::Date::from_calendar(
+        number(path, n, line, (15, 18))? as i64,
+        number(path, n, line, (20, 21))? as u8,
+        day.trunc() as u8,
+        time::Angle::from_turns(day.fract()),
+    );
+    let body = CelObj::Minor(minor::MinorBody {
+        q: number(path, n, line, (31, 39))?,
+        e: number(path, n, line, (42, 49))?,
+        i: deg((72, 79))?,
+        node: deg((62, 69))?,
+        peri: deg((52, 59))?,
+        tp,
+        mag: minor::MagLaw::Comet(
+            number(path, n, line, (92, 95))?,
+            number(path, n, line, (97, 100))?,
+        ),
+    });
+    let name = columns(path, n, line, (103, 158))?.to_lowercase();
+    if name.is_empty() {
+        return Err(Error::BadCatalog(path.to_string(), n, 103, "Missing name"));
+    }
+    // Periodic comets are numbered, like 1P, unnumbered ones like P/2019 LD2 only go by the full name
+    let numbered = |p: &str| {
+        let digits = p.trim_end_matches(['p', 'd']);
+        !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+    };
+    let short = match name.split_once('/') {
+        Some((p, n)) if numbered(p) => {
+            vec![p.to_string(), n.to_string()]
+        }
+        _ => vec![],
+    };
+    Ok(std::iter::once(name)
+        .chain(short)
+        .map(|x| (x, body.clone()))
+        .collect())
+}
+
+/// Reads earth satellites from a file of two-line elements
+///
+/// Each satellite can have a line with its name before its elements (3LE), or not, in which case it's
+/// named by its catalog number. Satellites can always be given by catalog number.
+pub fn read_tle(path: &str) -> Result<Vec<(String, CelObj)>, Error> {
+    let text = std::fs::read_to_string(path).map_err(|e| Error::Io(format!("{path}: {e}")))?;
+    let lines: Vec<(usize, &str)> = text
+        .lines()
+        .enumerate()
+        .map(|(i, l)| (i + 1, l.trim_end()))
+        .filter(|(_, l)| !l.is_empty())
+        .collect();
+
+    let mut found = vec![];
+    let mut i = 0;
+    while i < lines.len() {
+        let (name, first) = if lines[i].1.starts_with("1 ") {
+            (None, i)
+        } else {
+            // Some sources mark the name line with a leading 0
+            let n = lines[i].1.strip_prefix("0 ").unwrap_or(lines[i].1);
+            (Some(n.trim().to_lowercase()), i + 1)
+        };
+        let (Some(&(n1, l1)), Some(&(n2, l2))) = (lines.get(first), lines.get(first + 1)) else {
+            return Err(Error::BadCatalog(
+                path.to_string(),
+                lines[i].0,
+                1,
+                "Incomplete elements",
+            ));
+        };
+        if !l1.starts_with("1 ") {
+            return Err(Error::BadCatalog(
+                path.to_string(),
+                n1,
+                1,
+                "Expected line 1 of elements",
+            ));
+        }
+        if !l2.starts_with("2 ") {
+            return Err(Error::BadCatalog(
+                path.to_string(),
+                n2,
+                1,
+                "Expected line 2 of elements",
+            ));
+        }
+
+        let deg = |c| Ok::<_, Error>(time::Angle::from_degrees(number(path, n2, l2, c)?));
+        // Numbers with an implied decimal point and exponent, like "-11606-4" for -0.11606e-4
+        let implied = |n, l, c: (usize, usize)| {
+            let s = columns(path, n, l, c)?;
+            let (mantissa, exp) = match s.rfind(['-', '+']) {
+                Some(x) if x > 0 => s.split_at(x),
+                _ => (s, "0"),
+            };
+            let (sign, digits) = match mantissa.strip_prefix('-') {
+                Some(d) => (-1.0, d),
+                None => (1.0, mantissa.trim_start_matches('+')),
+            };
+            format!("0.{digits}e{exp}")
+                .parse::<f64>()
+                .map(|x| sign * x)
+                .ok()
+                .ok_or(Error::BadCatalog(
+                    path.to_string(),
+                    n,
+                    c.0,
+                    "Expected a number",
+                ))
+        };
+
+        let year = number(path, n1, l1, (19, 20))? as i64;
+        let year = if year < 57 { 2000 + year } else { 1900 + year };
+        let day = number(path, n1, l1, (21, 32))?;
+        let epoch = time::Date::from_julian(
+            time::Date::from_calendar(year, 1, 1, time::Angle::default()).julian() + day - 1.0,
+        );
+        let sat = CelObj::Satellite(sat::Satellite {
+            epoch,
+            incl: deg((9, 16))?,
+            node: deg((18, 25))?,
+            ecc: implied(n2, l2, (27, 33))?,
+            argp: deg((35, 42))?,
+            mean_anomaly: deg((44, 51))?,
+            mean_motion: number(path, n2, l2, (53, 63))?,
+            bstar: implied(n1, l1, (54, 61))?,
+        });
+
+        let catnum = columns(path, n1, l1, (3, 7))?
+            .trim_start_matches('0')
+            .to_string();
+        // Names like "ISS (ZARYA)" can also be used without the part in brackets
+        let short = name
+            .as_ref()
+            .and_then(|x| x.split_once(" ("))
+            .map(|x| x.0.to_string());
+        found.extend(
+            name.into_iter()
+                .chain(short)
+                .chain([catnum])
+                .map(|x| (x, sat.clone())),
+        );
+        i = first + 2;
+    }
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Right ascension and declination in the J2000 frame, in arcseconds
+    fn j2000_arcsec(s: &Star, d: time::Date) -> (f64, f64) {
+        let (x, y, z) = s.locationcart(d);
+        let (ra, de) = coord::Coord::from_cartesian(x, y, z).equatorial();
+        (ra.degrees() * 3600.0, de.to_latitude().degrees() * 3600.0)
+    }
+
+    #[test]
+    fn barnards_star() {
+        let CelObj::Star(s) = &read()["barnard's star"] else {
+            panic!("Barnard's star isn't a star");
+        };
+        // Hipparcos proper motion, -797.84 and +10326.93 mas/yr, over 50 years
+        let d = time::Date::from_calendar(2050, 1, 1, time::Angle::from_turns(0.5));
+        let (a0, d0) = j2000_arcsec(s, time::J2000);
+        let (a1, d1) = j2000_arcsec(s, d);
+        let cos_de = s.loc_j2k.equatorial().1.cos();
+        assert!(((a1 - a0) * cos_de + 39.89).abs() < 0.5, "{}", a1 - a0);
+        assert!((d1 - d0 - 516.35).abs() < 0.5, "{}", d1 - d0);
+        // Its declination passes +4°50′
+        assert!((d1 / 3600.0 - (4.0 + 50.0 / 60.0 + 12.5 / 3600.0)).abs() < 1.0 / 3600.0);
+    }
+
+    /// Sirius, as it's given in each kind of star catalog
+    const SIRIUS_CSV: &str = "name,ra,dec,mag,parallax,pm ra,pm dec,rv
+sirius,101.28715539,-16.71611582,-1.44,379.21,-546.01,-1223.08,-5.5
+";
+    const SIRIUS_HIP: &str = "H|       32349| |06 45 08.92|-16 42 58.0|-1.44|1|H|101.28715539|-16.71611582| |  379.21|  -546.01| -1223.08|  1.46|";
+    const SIRIUS_BSC: &str = "2491  9Alp CMa                                                             064508.9-164258            -1.46                                         -0.553-1.205  .375-008";
+
+    #[test]
+    fn star_catalogs() {
+        let csv = stars_csv("stars.csv", SIRIUS_CSV).unwrap();
+        let hip = stars_hipparcos("hip_main.dat", SIRIUS_HIP).unwrap();
+        let bsc = stars_bsc("catalog", SIRIUS_BSC).unwrap();
+        let names = |x: &[(String, CelObj)]| x.iter().map(|(n, _)| n.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&csv), ["sirius"]);
+        assert_eq!(names(&hip), ["hip32349"]);
+        assert_eq!(names(&bsc), ["hr2491", "alp cma", "9 cma"]);
+
+        let CelObj::Star(sirius) = &read()["sirius"] else {
+            panic!("Sirius isn't a star");
+        };
+        for (_, s) in csv.iter().chain(&hip).chain(&bsc) {
+            let CelObj::Star(s) = s else {
+                panic!("Not a star");
+            };
+            // The Bright Star Catalog gives positions to a second of arc
+            assert!(s.loc_j2k.dist(sirius.loc_j2k).degrees() < 1.0 / 3600.0);
+            assert!((s.pm_dec.to_latitude().degrees() * 3600.0 + 1.22).abs() < 0.02);
+            assert!((s.pi.degrees() * 3600.0 - 0.379).abs() < 0.005);
+        }
+        assert!(matches!(&csv[0].1, CelObj::Star(s) if s.rv == -5.5));
+        assert!(matches!(&hip[0].1, CelObj::Star(s) if s.rv == 0.0));
+    }
+
+    #[test]
+    fn star_catalog_errors() {
+        let error = |x: Result<Vec<(String, CelObj)>, Error>| x.unwrap_err().to_string();
+        let bad = SIRIUS_CSV.replace("-16.71611582", "-16.7161158x");
+        assert_eq!(
+            error(stars_csv("stars.csv", &bad)),
+            "stars.csv:2:21: Expected a number"
+        );
+        let bad = SIRIUS_BSC.replace("08.9-16", "08.9?16");
+        assert_eq!(
+            error(stars_bsc("catalog", &bad)),
+            "catalog:1:84: Expected a sign"
+        );
+        let short = "H|       32349| |06 45 08.92|-16 42 58.0|-1.44";
+        assert_eq!(
+            error(stars_hipparcos("hip_main.dat", short)),
+            "hip_main.dat:1:47: Missing column"
+        );
+    }
+
+    #[test]
+    fn deep_sky() {
+        let cat = read();
+        let CelObj::DeepSky(m31) = &cat["m31"] else {
+            panic!("M31 isn't a deep sky object");
+        };
+        assert_eq!(cat["ngc224"], cat["m31"]);
+        assert_eq!(cat["andromeda galaxy"], cat["m31"]);
+        assert_eq!(m31.kind, DsoKind::Galaxy);
+        assert_eq!((m31.mag, m31.size), (Some(3.4), (178.0, 63.0)));
+        // 00h42m44.3s +41°16′09″
+        let published = coord::Coord::from_equatorial(
+            time::Angle::from_clock(0, 42, 44.3),
+            time::Angle::from_degrees(41.0 + 16.0 / 60.0 + 9.0 / 3600.0),
+        );
+        assert!(m31.loc_j2k.dist(published).degrees() < 1.0 / 60.0);
+
+        assert!(
+            matches!(&cat["crab nebula"], CelObj::DeepSky(x) if x.kind == DsoKind::SupernovaRemnant)
+        );
+        // Dark nebulae have no magnitude, so no surface brightness either
+        let CelObj::DeepSky(coalsack) = &cat["coalsack"] else {
+            panic!("The Coalsack isn't a deep sky object");
+        };
+        assert_eq!(coalsack.kind, DsoKind::DarkNebula);
+        assert_eq!(coalsack.surface_brightness(), None);
+    }
+
+    /// Comet Encke in 1990 (Meeus, Example 33.a), and Ceres, after a header like MPCORB.DAT's
+    const ELEMENTS: &str = "MPCORB header
+-------------------------------------------------------------------------------
+0002P         1990 10 28.5450  0.3308859  0.850220  186.2335  334.7501   11.9452  19901006  11.5  6.0  2P/Encke
+00001    3.34  0.15 K2555 188.70269   73.27343   80.25221   10.58780  0.0794013  0.21424651   2.7660512                                                               (1) Ceres
+";
+
+    /// Reads elements written to a temporary file
+    fn elements(name: &str, text: &str) -> Result<Catalog, Error> {
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, text).unwrap();
+        let found = read_elements(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+        Ok(found?.into_iter().collect())
+    }
+
+    #[test]
+    fn mpc_elements() {
+        let cat = elements("deskephem-elements.txt", ELEMENTS).unwrap();
+        let mut names: Vec<&str> = cat.keys().map(String::as_str).collect();
+        names.sort();
+        assert_eq!(names, ["2p", "2p/encke", "ceres", "encke"]);
+
+        let CelObj::Minor(ceres) = &cat["ceres"] else {
+            panic!("Ceres isn't a minor planet");
+        };
+        assert!((ceres.q - 2.7660512 * (1.0 - 0.0794013)).abs() < 1e-9);
+        assert_eq!(ceres.mag, minor::MagLaw::HG(3.34, 0.15));
+
+        // Geocentric and J2000, 10h34m14.2s +19°09′31″ on 1990 October 6,
+        // to within the accuracy of pracstro's earth
+        let CelObj::Minor(encke) = &cat["encke"] else {
+            panic!("Encke isn't a comet");
+        };
+        let d = time::Date::from_calendar(1990, 10, 6, time::Angle::from_turns(0.0));
+        let (ra, de) = encke.location(d).precess(d, time::J2000).equatorial();
+        assert!((ra.degrees() - 158.558965).abs() < 0.005);
+        assert!((de.to_latitude().degrees() - 19.158496).abs() < 0.005);
+    }
+
+    #[test]
+    fn mpc_errors() {
+        let path = std::env::temp_dir().join("deskephem-bad-elements.txt");
+        let bad = ELEMENTS.replace("0.0794013", "0.07940x3");
+        let found = elements("deskephem-bad-elements.txt", &bad).map(|_| ());
+        assert_eq!(
+            found.unwrap_err().to_string(),
+            format!("{}:4:71: Expected a number", path.display())
+        );
+        let ceres = ELEMENTS.lines().last().unwrap();
+        let short = ELEMENTS.replace(ceres, &ceres[..50]);
+        let found = elements("deskephem-bad-elements.txt", &short).map(|_| ());
+        assert_eq!(
+            found.unwrap_err().to_string(),
+            format!("{}:4:93: Line too short", path.display())
+        );
+    }
+}
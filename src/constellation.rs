@@ -0,0 +1,191 @@
+use pracstro::{coord, time};
+
+/// The epoch the boundaries were drawn in, B1875.0
+const B1875: f64 = 2_405_889.258_550_475;
+
+/// Abbreviations and names of the 88 constellations
+const NAMES: [(&str, &str); 88] = [
+    ("And", "Andromeda"),
+    ("Ant", "Antlia"),
+    ("Aps", "Apus"),
+    ("Aqr", "Aquarius"),
+    ("Aql", "Aquila"),
+    ("Ara", "Ara"),
+    ("Ari", "Aries"),
+    ("Aur", "Auriga"),
+    ("Boo", "Boötes"),
+    ("Cae", "Caelum"),
+    ("Cam", "Camelopardalis"),
+    ("Cnc", "Cancer"),
+    ("CVn", "Canes Venatici"),
+    ("CMa", "Canis Major"),
+    ("CMi", "Canis Minor"),
+    ("Cap", "Capricornus"),
+    ("Car", "Carina"),
+    ("Cas", "Cassiopeia"),
+    ("Cen", "Centaurus"),
+    ("Cep", "Cepheus"),
+    ("Cet", "Cetus"),
+    ("Cha", "Chamaeleon"),
+    ("Cir", "Circinus"),
+    ("Col", "Columba"),
+    ("Com", "Coma Berenices"),
+    ("CrA", "Corona Australis"),
+    ("CrB", "Corona Borealis"),
+    ("Crv", "Corvus"),
+    ("Crt", "Crater"),
+    ("Cru", "Crux"),
+    ("Cyg", "Cygnus"),
+    ("Del", "Delphinus"),
+    ("Dor", "Dorado"),
+    ("Dra", "Draco"),
+    ("Equ", "Equuleus"),
+    ("Eri", "Eridanus"),
+    ("For", "Fornax"),
+    ("Gem", "Gemini"),
+    ("Gru", "Grus"),
+    ("Her", "Hercules"),
+    ("Hor", "Horologium"),
+    ("Hya", "Hydra"),
+    ("Hyi", "Hydrus"),
+    ("Ind", "Indus"),
+    ("Lac", "Lacerta"),
+    ("Leo", "Leo"),
+    ("LMi", "Leo Minor"),
+    ("Lep", "Lepus"),
+    ("Lib", "Libra"),
+    ("Lup", "Lupus"),
+    ("Lyn", "Lynx"),
+    ("Lyr", "Lyra"),
+    ("Men", "Mensa"),
+    ("Mic", "Microscopium"),
+    ("Mon", "Monoceros"),
+    ("Mus", "Musca"),
+    ("Nor", "Norma"),
+    ("Oct", "Octans"),
+    ("Oph", "Ophiuchus"),
+    ("Ori", "Orion"),
+    ("Pav", "Pavo"),
+    ("Peg", "Pegasus"),
+    ("Per", "Perseus"),
+    ("Phe", "Phoenix"),
+    ("Pic", "Pictor"),
+    ("Psc", "Pisces"),
+    ("PsA", "Piscis Austrinus"),
+    ("Pup", "Puppis"),
+    ("Pyx", "Pyxis"),
+    ("Ret", "Reticulum"),
+    ("Sge", "Sagitta"),
+    ("Sgr", "Sagittarius"),
+    ("Sco", "Scorpius"),
+    ("Scl", "Sculptor"),
+    ("Sct", "Scutum"),
+    ("Ser", "Serpens"),
+    ("Sex", "Sextans"),
+    ("Tau", "Taurus"),
+    ("Tel", "Telescopium"),
+    ("Tri", "Triangulum"),
+    ("TrA", "Triangulum Australe"),
+    ("Tuc", "Tucana"),
+    ("UMa", "Ursa Major"),
+    ("UMi", "Ursa Minor"),
+    ("Vel", "Vela"),
+    ("Vir", "Virgo"),
+    ("Vol", "Volans"),
+    ("Vul", "Vulpecula"),
+];
+
+/// Rigorous precession between two dates (Meeus, Astronomical Algorithms, Ch. 21)
+///
+/// [`coord::Coord::precess`] is a linear approximation, which falls apart near the poles over the
+/// 150 years back to the epoch of the boundaries.
+pub fn precess(c: coord::Coord, from: time::Date, to: time::Date) -> coord::Coord {
+    let tt = (from.julian() - time::J2000.julian()) / 36525.0;
+    let t = (to.julian() - from.julian()) / 36525.0;
+    let arcsec = |x: f64| time::Angle::from_degrees(x / 3600.0);
+    let zeta = arcsec(
+        (2306.2181 + 1.39656 * tt - 0.000139 * tt * tt) * t
+            + (0.30188 - 0.000344 * tt) * t * t
+            + 0.017998 * t * t * t,
+    );
+    let z = arcsec(
+        (2306.2181 + 1.39656 * tt - 0.000139 * tt * tt) * t
+            + (1.09468 + 0.000066 * tt) * t * t
+            + 0.018203 * t * t * t,
+    );
+    let theta = arcsec(
+        (2004.3109 - 0.85330 * tt - 0.000217 * tt * tt) * t
+            - (0.42665 + 0.000217 * tt) * t * t
+            - 0.041833 * t * t * t,
+    );
+
+    let (ra, de) = c.equatorial();
+    let a = de.cos() * (ra + zeta).sin();
+    let b = theta.cos() * de.cos() * (ra + zeta).cos() - theta.sin() * de.sin();
+    let c = theta.sin() * de.cos() * (ra + zeta).cos() + theta.cos() * de.sin();
+    coord::Coord::from_equatorial(
+        time::Angle::atan2(a, b) + z,
+        time::Angle::asin(c.clamp(-1.0, 1.0)),
+    )
+}
+
+/// The abbreviation of the constellation a position (in the equinox of `d`) is in
+///
+/// Uses the boundaries of Delporte (1930), as tabulated by Roman (1987): each line is a band of
+/// right ascension, above a declination, ordered from north to south. The first one containing the position
+/// precessed to B1875 is its constellation.
+pub fn abbreviation(c: coord::Coord, d: time::Date) -> &'static str {
+    let (ra, de) = precess(c, d, time::Date::from_julian(B1875)).equatorial();
+    let (ra, de) = (ra.decimal(), de.to_latitude().degrees());
+    include_str!("dat/boundaries.dat")
+        .lines()
+        .filter(|l| !l.starts_with('#'))
+        .map(|l| l.split_whitespace().collect::<Vec<&str>>())
+        .find(|p| {
+            let bound = |i: usize| p[i].parse::<f64>().unwrap();
+            de >= bound(2) && ra >= bound(0) && ra < bound(1)
+        })
+        .map_or("Oct", |p| p[3])
+}
+
+/// The full name of a constellation from its abbreviation
+pub fn name(abbr: &str) -> &'static str {
+    NAMES
+        .iter()
+        .find(|x| x.0 == abbr)
+        .map_or("Unknown", |x| x.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A position from its right ascension and declination in degrees
+    fn equ(ra: f64, de: f64) -> coord::Coord {
+        coord::Coord::from_equatorial(time::Angle::from_degrees(ra), time::Angle::from_degrees(de))
+    }
+
+    #[test]
+    fn meeus_precession() {
+        // Example 21.b, θ Persei from J2000 to 2028 November 13.19
+        let to = time::Date::from_julian(2_462_088.69);
+        let (ra, de) = precess(equ(41.054063, 49.227750), time::J2000, to).equatorial();
+        assert!((ra.degrees() - 41.547214).abs() < 0.5 / 3600.0);
+        assert!((de.degrees() - 49.348483).abs() < 0.5 / 3600.0);
+    }
+
+    #[test]
+    fn stars_on_boundaries() {
+        // Alpheratz is on the corner of Pegasus' square but in Andromeda, Elnath is on
+        // Auriga's outline but in Taurus
+        assert_eq!(abbreviation(equ(2.096911, 29.090432), time::J2000), "And");
+        assert_eq!(abbreviation(equ(81.572971, 28.607452), time::J2000), "Tau");
+        // Either side of +28°30′, where the boundary between them was drawn in 1875
+        let b1875 = time::Date::from_julian(B1875);
+        assert_eq!(abbreviation(equ(80.0, 28.49), b1875), "Tau");
+        assert_eq!(abbreviation(equ(80.0, 28.51), b1875), "Aur");
+        assert_eq!(abbreviation(equ(0.0, 90.0), time::J2000), "UMi");
+        assert_eq!(abbreviation(equ(0.0, -90.0), time::J2000), "Oct");
+        assert_eq!(name("Tau"), "Taurus");
+    }
+}
@@ -0,0 +1,460 @@
+use crate::error::Error;
+use crate::value::RefFrame;
+use crate::{event, lunar, query};
+use pracstro::time;
+use std::fmt;
+
+const KM_PER_AU: f64 = 149_597_870.7;
+const EARTH_RADIUS: f64 = 6378.14; // Kilometers
+const SUN_RADIUS: f64 = 696_000.0;
+/// The radius used for eclipse predictions, a little over the mean radius to take in the mountains of the limb
+const MOON_RADIUS: f64 = 1738.1;
+
+/// Days in a synodic month, and the julian date of the first new moon of 2000
+const SYNODIC_MONTH: f64 = 29.530_588_861;
+const FIRST_NEW_MOON: f64 = 2_451_550.097_66;
+
+/// Which body is eclipsed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Body {
+    Sun,
+    Moon,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Kind {
+    Total,
+    Annular,
+    /// Annular along part of the path and total along the rest
+    Hybrid,
+    Partial,
+    /// The moon only passes through the earth's penumbra
+    Penumbral,
+}
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Kind::Total => "Total",
+                Kind::Annular => "Annular",
+                Kind::Hybrid => "Hybrid",
+                Kind::Partial => "Partial",
+                Kind::Penumbral => "Penumbral",
+            }
+        )
+    }
+}
+
+/// Details of an eclipse that can be given as properties
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Circumstance {
+    Kind,
+    Magnitude,
+    Gamma,
+    Begin,
+    TotalityBegin,
+    Maximum,
+    TotalityEnd,
+    End,
+    /// Fraction of the area of the sun covered at the observer's maximum
+    Obscuration,
+    /// Apparent altitude of the sun or moon seen from the observer at the beginning, maximum, and
+    /// end, to tell if the eclipse can be seen from there
+    BeginAltitude,
+    MaximumAltitude,
+    EndAltitude,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Eclipse {
+    pub body: Body,
+    pub kind: Kind,
+    /// Universal time of greatest eclipse
+    pub greatest: time::Date,
+    /// For the sun, the fraction of its diameter covered where the eclipse is greatest. For the moon,
+    /// the fraction of its diameter in the umbra, or the penumbra for penumbral eclipses
+    pub magnitude: f64,
+    /// Least distance of the axis of the moon's shadow (or the moon, for lunar eclipses) from the
+    /// center of the earth, in equatorial radii, positive to the north
+    pub gamma: f64,
+    /// Radius of the umbral cone at the earth, in equatorial radii, negative when the umbra reaches it
+    u: f64,
+    /// Speed of the moon relative to the shadow, in equatorial radii per hour
+    n: f64,
+}
+
+/// What's seen of a solar eclipse from the observer
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Local {
+    pub begin: time::Date,
+    pub maximum: time::Date,
+    pub end: time::Date,
+    /// Beginning and end of totality or annularity, if the observer is in the path
+    pub central: Option<(time::Date, time::Date)>,
+    /// Fraction of the sun's diameter covered at maximum
+    pub magnitude: f64,
+    /// Fraction of the sun's area covered at maximum
+    pub obscuration: f64,
+}
+
+/// The eclipse at lunation `k` (whole for new moons, and halves for full moons) counted from the
+/// first new moon of 2000, if there is one
+///
+/// Meeus, Astronomical Algorithms, Ch. 54. Times are good to a few minutes, and gamma and the
+/// magnitude to a few thousandths.
+fn at_lunation(k: f64) -> Option<Eclipse> {
+    let rad = |x: f64| x.to_radians();
+    let t = k / 1236.85;
+    let f = rad(
+        160.7108 + 390.670_502_84 * k - 0.001_611_8 * t * t - 0.000_002_27 * t.powi(3)
+            + 0.000_000_011 * t.powi(4),
+    );
+    // Too far from a node of the moon's orbit for the shadows to meet
+    if f.sin().abs() > 0.36 {
+        return None;
+    }
+    let body = if k.fract().abs() > 0.25 {
+        Body::Moon
+    } else {
+        Body::Sun
+    };
+    let jde = FIRST_NEW_MOON + SYNODIC_MONTH * k + 0.000_154_37 * t * t - 0.000_000_150 * t.powi(3)
+        + 0.000_000_000_73 * t.powi(4);
+    let e = 1.0 - 0.002_516 * t - 0.000_007_4 * t * t;
+    let m = rad(2.5534 + 29.105_356_70 * k - 0.000_001_4 * t * t - 0.000_000_11 * t.powi(3));
+    let mp = rad(
+        201.5643 + 385.816_935_28 * k + 0.010_758_2 * t * t + 0.000_012_38 * t.powi(3)
+            - 0.000_000_058 * t.powi(4),
+    );
+    let omega = rad(124.7746 - 1.563_755_88 * k + 0.002_067_2 * t * t + 0.000_002_15 * t.powi(3));
+    let f1 = f - rad(0.02665) * omega.sin();
+    let a1 = rad(299.77 + 0.107_408 * k - 0.009_173 * t * t);
+
+    let anomalies = match body {
+        Body::Sun => -0.4075 * mp.sin() + 0.1721 * e * m.sin(),
+        Body::Moon => -0.4065 * mp.sin() + 0.1727 * e * m.sin(),
+    };
+    let jde = jde + anomalies + 0.0161 * (2.0 * mp).sin() - 0.0097 * (2.0 * f1).sin()
+        + 0.0073 * e * (mp - m).sin()
+        - 0.0050 * e * (mp + m).sin()
+        - 0.0023 * (mp - 2.0 * f1).sin()
+        + 0.0021 * e * (2.0 * m).sin()
+        + 0.0012 * (mp + 2.0 * f1).sin()
+        + 0.0006 * e * (2.0 * mp + m).sin()
+        - 0.0004 * (3.0 * mp).sin()
+        - 0.0003 * e * (m + 2.0 * f1).sin()
+        + 0.0003 * a1.sin()
+        - 0.0002 * e * (m - 2.0 * f1).sin()
+        - 0.0002 * e * (2.0 * mp - m).sin()
+        - 0.0002 * omega.sin();
+
+    let p = 0.2070 * e * m.sin() + 0.0024 * e * (2.0 * m).sin() - 0.0392 * mp.sin()
+        + 0.0116 * (2.0 * mp).sin()
+        - 0.0073 * e * (mp + m).sin()
+        + 0.0067 * e * (mp - m).sin()
+        + 0.0118 * (2.0 * f1).sin();
+    let q = 5.2207 - 0.0048 * e * m.cos() + 0.0020 * e * (2.0 * m).cos()
+        - 0.3299 * mp.cos()
+        - 0.0060 * e * (mp + m).cos()
+        + 0.0041 * e * (mp - m).cos();
+    let gamma = (p * f1.cos() + q * f1.sin()) * (1.0 - 0.0048 * f1.cos().abs());
+    let u = 0.0059 + 0.0046 * e * m.cos() - 0.0182 * mp.cos() + 0.0004 * (2.0 * mp).cos()
+        - 0.0005 * (m + mp).cos();
+    let g = gamma.abs();
+
+    let (kind, magnitude) = match body {
+        Body::Sun if g > 1.5433 + u => return None,
+        // Central eclipses. The magnitude is that on the axis, where the moon is an earth radius nearer
+        Body::Sun if g < 0.9972 => {
+            let kind = if u < 0.0 {
+                Kind::Total
+            } else if u < 0.0047 && u < 0.00464 * (1.0 - g * g).sqrt() {
+                Kind::Hybrid
+            } else {
+                Kind::Annular
+            };
+            let greatest = time::Date::from_julian(jde);
+            let (_, _, dm) = lunar::moon(greatest);
+            let (_, ds) = lunar::sun(greatest);
+            let near = dm - EARTH_RADIUS * (1.0 - g * g).sqrt();
+            let ratio = (MOON_RADIUS / near) / (SUN_RADIUS / (ds * KM_PER_AU));
+            (kind, ratio)
+        }
+        Body::Sun => (Kind::Partial, (1.5433 + u - g) / (0.5461 + 2.0 * u)),
+        Body::Moon => {
+            let umbral = (1.0128 - u - g) / 0.5450;
+            let penumbral = (1.5573 + u - g) / 0.5450;
+            match (umbral, penumbral) {
+                (_, ..=0.0) => return None,
+                (..=0.0, _) => (Kind::Penumbral, penumbral),
+                (..1.0, _) => (Kind::Partial, umbral),
+                _ => (Kind::Total, umbral),
+            }
+        }
+    };
+    let td = time::Date::from_julian(jde);
+    Some(Eclipse {
+        body,
+        kind,
+        greatest: time::Date::from_julian(jde - lunar::delta_t(td)),
+        magnitude,
+        gamma,
+        u,
+        n: 0.5458 + 0.0400 * mp.cos(),
+    })
+}
+
+/// The lunation of the first new or full moon (depending on the body) before the date
+fn lunation_before(body: Body, d: time::Date) -> f64 {
+    let k = ((d.julian() - FIRST_NEW_MOON) / SYNODIC_MONTH).floor() - 1.0;
+    match body {
+        Body::Sun => k,
+        Body::Moon => k + 0.5,
+    }
+}
+
+/// Every eclipse of the body with its greatest eclipse between `start` and `end`
+pub fn between(body: Body, start: time::Date, end: time::Date) -> Vec<Eclipse> {
+    let first = lunation_before(body, start);
+    (0..)
+        .map(|i| first + i as f64)
+        .take_while(|k| FIRST_NEW_MOON + SYNODIC_MONTH * (k - 1.0) < end.julian())
+        .filter_map(at_lunation)
+        .filter(|e| (start.julian()..end.julian()).contains(&e.greatest.julian()))
+        .collect()
+}
+
+/// The first eclipse of the body with its greatest eclipse after the date
+pub fn next(body: Body, d: time::Date) -> Eclipse {
+    let first = lunation_before(body, d);
+    (0..)
+        .filter_map(|i| at_lunation(first + i as f64))
+        .find(|e| e.greatest.julian() > d.julian())
+        .expect("There are eclipses every six months")
+}
+
+impl Eclipse {
+    /// The instants a lunar eclipse is `r` earth radii past greatest eclipse, when the shadow is that big
+    fn half(&self, r: f64) -> Option<(time::Date, time::Date)> {
+        let squared = r * r - self.gamma * self.gamma;
+        if squared <= 0.0 {
+            return None;
+        }
+        let days = squared.sqrt() / self.n / 24.0;
+        Some((
+            time::Date::from_julian(self.greatest.julian() - days),
+            time::Date::from_julian(self.greatest.julian() + days),
+        ))
+    }
+
+    /// Beginning and end of a lunar eclipse: the partial phase, or the penumbral one if there isn't one
+    pub fn contacts(&self) -> (time::Date, time::Date) {
+        self.half(1.0128 - self.u)
+            .or(self.half(1.5573 + self.u))
+            .expect("The moon is in the penumbra during an eclipse")
+    }
+
+    /// Beginning and end of totality of a lunar eclipse
+    pub fn totality(&self) -> Option<(time::Date, time::Date)> {
+        self.half(0.4678 - self.u)
+    }
+}
+
+/// Distance between the centers of the sun and moon seen from the observer, and their radii, in degrees
+fn discs(d: time::Date, rf: &RefFrame) -> Result<(f64, f64, f64), Error> {
+    if rf.latlong.is_none() {
+        return Err(Error::MissingLocation);
+    }
+    let at = RefFrame { date: d, ..*rf };
+    let (moon, near) = lunar::topocentric(&at);
+    let ds = lunar::sun(d).1;
+    let sun = query::topocentric(lunar::sun_position(d), ds, &at);
+    Ok((
+        moon.dist(sun).degrees(),
+        (SUN_RADIUS / (ds * KM_PER_AU)).asin().to_degrees(),
+        (MOON_RADIUS / near).asin().to_degrees(),
+    ))
+}
+
+/// Area two discs of radius `a` and `b` overlap when their centers are `s` apart
+fn overlap(s: f64, a: f64, b: f64) -> f64 {
+    use std::f64::consts::PI;
+    if s >= a + b {
+        0.0
+    } else if s <= (a - b).abs() {
+        PI * a.min(b).powi(2)
+    } else {
+        let lens = ((-s + a + b) * (s + a - b) * (s - a + b) * (s + a + b)).sqrt();
+        a * a * ((s * s + a * a - b * b) / (2.0 * s * a)).acos()
+            + b * b * ((s * s + b * b - a * a) / (2.0 * s * b)).acos()
+            - lens / 2.0
+    }
+}
+
+/// How far the observer can be from greatest eclipse and still see it, in days
+const LOCAL_WINDOW: f64 = 0.25;
+
+/// The solar eclipse as seen from the observer, if it's seen there at all
+///
+/// Whether the sun is above the horizon isn't taken into account, the altitudes of the sun at the
+/// contacts ([`Circumstance::BeginAltitude`] and so on) tell if it can be seen.
+pub fn local(e: &Eclipse, rf: &RefFrame) -> Result<Option<Local>, Error> {
+    let covered = |d| discs(d, rf).map(|(s, rs, rm)| rs + rm - s);
+    let central = |d| discs(d, rf).map(|(s, rs, rm)| (rm - rs).abs() - s);
+    let start = time::Date::from_julian(e.greatest.julian() - LOCAL_WINDOW);
+    let end = time::Date::from_julian(e.greatest.julian() + LOCAL_WINDOW);
+    // Five minutes, a partial eclipse is seen for at least that long unless it's barely grazing
+    let c = event::crossings(covered, start, end, 1.0 / 288.0)?;
+    let (Some(begin), Some(finish)) = (c.iter().find(|c| c.rising), c.iter().find(|c| !c.rising))
+    else {
+        return Ok(None);
+    };
+    let (maximum, gap) = event::maximum(covered, begin.date, finish.date)?;
+    let (s, rs, rm) = discs(maximum, rf)?;
+    let central = if central(maximum)? > 0.0 {
+        let span = maximum.julian() - begin.date.julian();
+        let before = event::next_crossing(central, begin.date, maximum, span, true)?;
+        let span = finish.date.julian() - maximum.julian();
+        let after = event::next_crossing(central, maximum, finish.date, span, false)?;
+        before.zip(after)
+    } else {
+        None
+    };
+    Ok(Some(Local {
+        begin: begin.date,
+        maximum,
+        end: finish.date,
+        central,
+        magnitude: gap / (2.0 * rs),
+        obscuration: overlap(s, rs, rm) / overlap(0.0, rs, rs),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value::Refraction;
+
+    /// Hours of the day in UT
+    fn hours(d: time::Date) -> f64 {
+        d.calendar().3.decimal()
+    }
+
+    fn near(x: f64, expected: f64, within: f64) -> bool {
+        (x - expected).abs() < within
+    }
+
+    /// Two minutes, which the method is good to
+    const MINUTES: f64 = 2.0 / 60.0;
+
+    #[test]
+    fn meeus_example() {
+        // Example 54.a, the partial eclipse of 1993 May 21
+        let e = at_lunation(-82.0).unwrap();
+        assert_eq!((e.body, e.kind), (Body::Sun, Kind::Partial));
+        assert!(near(e.gamma, 1.1348, 0.0001));
+        assert!(near(e.u, 0.0097, 0.0001));
+        assert!(near(e.magnitude, 0.740, 0.001));
+    }
+
+    #[test]
+    fn published_solar() {
+        // Greatest eclipse, gamma and magnitude from the NASA eclipse website
+        let e = next(
+            Body::Sun,
+            time::Date::from_calendar(2025, 3, 1, time::Angle::from_turns(0.0)),
+        );
+        assert_eq!(e.kind, Kind::Partial);
+        let (y, m, d, _) = e.greatest.calendar();
+        assert_eq!((y, m, d), (2025, 3, 29));
+        assert!(near(
+            hours(e.greatest),
+            10.0 + 47.0 / 60.0 + 25.0 / 3600.0,
+            MINUTES
+        ));
+        assert!(near(e.gamma, 1.0405, 0.002));
+        assert!(near(e.magnitude, 0.9376, 0.005));
+
+        let e = at_lunation(300.0).unwrap();
+        assert_eq!(e.kind, Kind::Total);
+        let (y, m, d, _) = e.greatest.calendar();
+        assert_eq!((y, m, d), (2024, 4, 8));
+        assert!(near(
+            hours(e.greatest),
+            18.0 + 17.0 / 60.0 + 21.0 / 3600.0,
+            MINUTES
+        ));
+        assert!(near(e.gamma, 0.3431, 0.002));
+        assert!(near(e.magnitude, 1.0566, 0.005));
+    }
+
+    #[test]
+    fn published_lunar() {
+        // The total lunar eclipse of 2025 March 14, from the NASA eclipse website
+        let e = next(
+            Body::Moon,
+            time::Date::from_calendar(2025, 3, 1, time::Angle::from_turns(0.0)),
+        );
+        assert_eq!(e.kind, Kind::Total);
+        assert!(near(
+            hours(e.greatest),
+            6.0 + 58.0 / 60.0 + 43.0 / 3600.0,
+            MINUTES
+        ));
+        assert!(near(e.gamma, 0.3485, 0.002));
+        assert!(near(e.magnitude, 1.1781, 0.005));
+        let (begin, end) = e.contacts();
+        assert!(near(
+            hours(begin),
+            5.0 + 9.0 / 60.0 + 40.0 / 3600.0,
+            MINUTES
+        ));
+        assert!(near(hours(end), 8.0 + 47.0 / 60.0 + 52.0 / 3600.0, MINUTES));
+        let (begin, end) = e.totality().unwrap();
+        assert!(near(
+            hours(begin),
+            6.0 + 26.0 / 60.0 + 6.0 / 3600.0,
+            MINUTES
+        ));
+        assert!(near(hours(end), 7.0 + 31.0 / 60.0 + 26.0 / 3600.0, MINUTES));
+    }
+
+    #[test]
+    fn published_local() {
+        // The total eclipse of 2024 April 8 seen from Dallas
+        let e = at_lunation(300.0).unwrap();
+        let deg = time::Angle::from_degrees;
+        let rf = RefFrame {
+            latlong: Some((deg(32.7767), deg(-96.797), 0.0)),
+            date: e.greatest,
+            refraction: Refraction::Standard,
+            from: None,
+        };
+        let l = local(&e, &rf).unwrap().unwrap();
+        assert!(near(
+            hours(l.begin),
+            17.0 + 23.0 / 60.0 + 20.0 / 3600.0,
+            MINUTES
+        ));
+        assert!(near(
+            hours(l.end),
+            20.0 + 2.0 / 60.0 + 48.0 / 3600.0,
+            MINUTES
+        ));
+        let (begin, end) = l.central.unwrap();
+        assert!(near(
+            hours(begin),
+            18.0 + 40.0 / 60.0 + 43.0 / 3600.0,
+            MINUTES
+        ));
+        assert!(near(
+            hours(end),
+            18.0 + 44.0 / 60.0 + 35.0 / 3600.0,
+            MINUTES
+        ));
+        assert!(l.magnitude > 1.0);
+        assert_eq!(l.obscuration, 1.0);
+    }
+}
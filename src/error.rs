@@ -0,0 +1,78 @@
+use std::fmt;
+
+/// Everything that can go wrong while parsing or running a query
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    /// The property needs an observer, but no `-l` was given
+    MissingLocation,
+    /// The property can't be computed for this kind of object (e.g. the phase of a star)
+    Unsupported(String, &'static str),
+    UnknownObject(String),
+    UnknownProperty(String),
+    BadDate(&'static str),
+    BadInterval(&'static str),
+    BadAngle(&'static str),
+    BadLocation(&'static str),
+    BadRefraction(&'static str),
+    /// A file couldn't be read
+    Io(String),
+    /// A catalog file is malformed: the file, line, column, and what was wrong
+    BadCatalog(String, usize, usize, &'static str),
+    /// The satellite's position couldn't be propagated to the date
+    Satellite(crate::sat::Sgp4Error),
+}
+
+impl Error {
+    /// Exit code of the CLI for this error
+    ///
+    /// 1 is left for unexpected failures, and 2 is used by clap for usage errors
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::MissingLocation => 3,
+            Error::Unsupported(..) => 4,
+            Error::UnknownObject(_) => 5,
+            Error::UnknownProperty(_) => 6,
+            Error::BadDate(_) => 7,
+            Error::BadInterval(_) => 8,
+            Error::BadAngle(_) => 9,
+            Error::BadLocation(_) => 10,
+            Error::BadRefraction(_) => 11,
+            Error::Io(_) => 12,
+            Error::BadCatalog(..) => 13,
+            Error::Satellite(_) => 14,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::MissingLocation => write!(f, "Need to specify a lat/long with -l"),
+            Error::Unsupported(p, kind) => write!(f, "Can't get {p} of {kind}"),
+            Error::UnknownObject(s) => write!(f, "Unknown Object \"{s}\""),
+            Error::UnknownProperty(s) => write!(f, "Unknown Property \"{s}\""),
+            Error::BadDate(e) => write!(f, "Invalid Date: {e}"),
+            Error::BadInterval(e) => write!(f, "Invalid Interval: {e}"),
+            Error::BadAngle(e) => write!(f, "Invalid Angle: {e}"),
+            Error::BadLocation(e) => write!(f, "Invalid Location: {e}"),
+            Error::BadRefraction(e) => write!(f, "Invalid Refraction: {e}"),
+            Error::Io(e) => write!(f, "{e}"),
+            Error::BadCatalog(file, l, c, e) => write!(f, "{file}:{l}:{c}: {e}"),
+            Error::Satellite(crate::sat::Sgp4Error::DeepSpace) => write!(
+                f,
+                "Satellites with periods over 225 minutes (deep space) aren't supported"
+            ),
+            Error::Satellite(crate::sat::Sgp4Error::Decayed) => {
+                write!(f, "Satellite has decayed by this date")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<crate::sat::Sgp4Error> for Error {
+    fn from(e: crate::sat::Sgp4Error) -> Self {
+        Error::Satellite(e)
+    }
+}
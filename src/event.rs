@@ -0,0 +1,727 @@
+use crate::error::Error;
+use crate::query::{property_of, Property};
+use crate::value::*;
+use crate::{lunar, moons, query, sidereal};
+use pracstro::{moon, sol, time};
+use std::fmt;
+
+/// One second, in days. Events are refined until they are known to this precision
+const PRECISION: f64 = 1.0 / 86400.0;
+
+/// An instant where a function changed sign
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Crossing {
+    pub date: time::Date,
+    /// If the function went from negative to positive
+    pub rising: bool,
+}
+
+/// Finds every instant between `start` and `end` where `f` crosses zero
+///
+/// `f` is sampled every `step` days, and each sign change is refined by bisection.
+/// Any crossings closer together than `step` can be missed, so it should be well
+/// under the shortest period of `f`.
+pub fn crossings<F>(
+    f: F,
+    start: time::Date,
+    end: time::Date,
+    step: f64,
+) -> Result<Vec<Crossing>, Error>
+where
+    F: Fn(time::Date) -> Result<f64, Error>,
+{
+    let mut found = vec![];
+    let (mut t0, mut f0) = (start.julian(), f(start)?);
+    while t0 < end.julian() {
+        let t1 = (t0 + step).min(end.julian());
+        let f1 = f(time::Date::from_julian(t1))?;
+        if (f0 < 0.0) != (f1 < 0.0) {
+            found.push(Crossing {
+                date: bisect(&f, t0, t1, f0 < 0.0)?,
+                rising: f0 < 0.0,
+            });
+        }
+        (t0, f0) = (t1, f1);
+    }
+    Ok(found)
+}
+
+/// The first crossing of zero after `start` in the direction given, if there is one before `end`
+pub fn next_crossing<F>(
+    f: F,
+    start: time::Date,
+    end: time::Date,
+    step: f64,
+    rising: bool,
+) -> Result<Option<time::Date>, Error>
+where
+    F: Fn(time::Date) -> Result<f64, Error>,
+{
+    let (mut t0, mut f0) = (start.julian(), f(start)?);
+    while t0 < end.julian() {
+        let t1 = (t0 + step).min(end.julian());
+        let f1 = f(time::Date::from_julian(t1))?;
+        if (f0 < 0.0) != (f1 < 0.0) && (f0 < 0.0) == rising {
+            return Ok(Some(bisect(&f, t0, t1, rising)?));
+        }
+        (t0, f0) = (t1, f1);
+    }
+    Ok(None)
+}
+
+/// Narrows down a sign change of `f` between the julian dates `a` and `b`
+fn bisect<F>(f: &F, mut a: f64, mut b: f64, rising: bool) -> Result<time::Date, Error>
+where
+    F: Fn(time::Date) -> Result<f64, Error>,
+{
+    while b - a > PRECISION {
+        let m = (a + b) / 2.0;
+        if (f(time::Date::from_julian(m))? < 0.0) == rising {
+            a = m;
+        } else {
+            b = m;
+        }
+    }
+    Ok(time::Date::from_julian((a + b) / 2.0))
+}
+
+/// How far events are searched for, in days
+///
+/// Anything that doesn't rise or set in this window is treated as always up or always down
+const WINDOW: f64 = 2.0;
+
+/// Sampling step for searches tied to the rotation of the earth, one hour
+const HOURLY: f64 = 1.0 / 24.0;
+
+/// How often the altitude of an object needs to be checked to not miss it rising or setting
+///
+/// Satellites can cross the sky in a few minutes, everything else takes hours
+fn sample_step(obj: &CelObj) -> f64 {
+    match obj {
+        CelObj::Satellite(_) => 1.0 / 1440.0,
+        _ => HOURLY,
+    }
+}
+
+/// The end of the window events are searched for in
+pub fn window_end(rf: &RefFrame) -> time::Date {
+    time::Date::from_julian(rf.date.julian() + WINDOW)
+}
+
+/// The instant `f` is largest between `start` and `end`, and its value there
+///
+/// `f` must only have one peak in the interval, this uses a golden section search.
+pub fn maximum<F>(f: F, start: time::Date, end: time::Date) -> Result<(time::Date, f64), Error>
+where
+    F: Fn(time::Date) -> Result<f64, Error>,
+{
+    const INVPHI: f64 = 0.618_033_988_749_895;
+    let at = |t: f64| f(time::Date::from_julian(t));
+    let (mut a, mut b) = (start.julian(), end.julian());
+    let (mut c, mut d) = (b - INVPHI * (b - a), a + INVPHI * (b - a));
+    let (mut fc, mut fd) = (at(c)?, at(d)?);
+    while b - a > PRECISION {
+        if fc > fd {
+            (b, d, fd) = (d, c, fc);
+            c = b - INVPHI * (b - a);
+            fc = at(c)?;
+        } else {
+            (a, c, fc) = (c, d, fd);
+            d = a + INVPHI * (b - a);
+            fd = at(d)?;
+        }
+    }
+    let t = (a + b) / 2.0;
+    Ok((time::Date::from_julian(t), at(t)?))
+}
+
+/// A pass of an object over the observer, from rising to setting
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Pass {
+    pub rise: time::Date,
+    /// The time of highest altitude
+    pub culmination: time::Date,
+    pub set: time::Date,
+    /// Geometric altitude at culmination, in degrees
+    pub max_alt: f64,
+}
+
+/// Every complete pass of the object over the observer that starts after the date, and ends before `end`
+pub fn passes(obj: &CelObj, rf: &RefFrame, end: time::Date) -> Result<Vec<Pass>, Error> {
+    let at = |d: time::Date| RefFrame { date: d, ..*rf };
+    let above_horizon = |d: time::Date| Ok(altitude(obj, &at(d))? - horizon_altitude(obj, &at(d)));
+    let found = crossings(above_horizon, rf.date, end, sample_step(obj))?;
+    found
+        .windows(2)
+        .filter(|x| x[0].rising && !x[1].rising)
+        .map(|x| {
+            let (culmination, max_alt) = maximum(|d| altitude(obj, &at(d)), x[0].date, x[1].date)?;
+            Ok(Pass {
+                rise: x[0].date,
+                culmination,
+                set: x[1].date,
+                max_alt,
+            })
+        })
+        .collect()
+}
+
+/// How far ahead phenomena of moons are searched for, in days. Titan takes 16 days to orbit
+const MOON_WINDOW: f64 = 20.0;
+
+/// The next time a phenomenon of a moon of Jupiter or Saturn begins
+///
+/// Whether the planet is above the horizon isn't taken into account.
+pub fn next_phenomenon(
+    m: moons::PlanetMoon,
+    rf: &RefFrame,
+    ph: moons::Phenomenon,
+) -> Result<Option<time::Date>, Error> {
+    let inside = |d: time::Date| Ok(m.phenomenon(d, ph));
+    let end = time::Date::from_julian(rf.date.julian() + MOON_WINDOW);
+    // Ten minutes, the shortest are about an hour long
+    next_crossing(inside, rf.date, end, 1.0 / 144.0, true)
+}
+
+/// Positions of a planet relative to the sun, seen from the observer
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Aspect {
+    /// In line with the sun, for planets further from the sun than the observer
+    Conjunction,
+    /// Between the observer and the sun
+    InferiorConjunction,
+    /// Behind the sun, for planets nearer the sun than the observer
+    SuperiorConjunction,
+    Opposition,
+    GreatestEast,
+    GreatestWest,
+    /// Stops moving east along the ecliptic, and starts moving west
+    StationRetrograde,
+    /// Stops moving west along the ecliptic, and starts moving east again
+    StationDirect,
+}
+impl Aspect {
+    /// If this is the aspect asked for. A plain conjunction stands for either kind
+    pub fn is(self, asked: Aspect) -> bool {
+        self == asked
+            || (asked == Aspect::Conjunction
+                && matches!(
+                    self,
+                    Aspect::InferiorConjunction | Aspect::SuperiorConjunction
+                ))
+    }
+}
+impl fmt::Display for Aspect {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Aspect::Conjunction => "Conjunction",
+                Aspect::InferiorConjunction => "Inferior Conjunction",
+                Aspect::SuperiorConjunction => "Superior Conjunction",
+                Aspect::Opposition => "Opposition",
+                Aspect::GreatestEast => "Greatest Elongation E",
+                Aspect::GreatestWest => "Greatest Elongation W",
+                Aspect::StationRetrograde => "Station (Retrograde)",
+                Aspect::StationDirect => "Station (Direct)",
+            }
+        )
+    }
+}
+
+/// How far ahead aspects and conjunctions are searched for, in days. A little over the longest
+/// synodic period of a planet, Mars'
+const SYNODIC_WINDOW: f64 = 800.0;
+
+/// Half the interval motion is measured over, in days
+const NUDGE: f64 = 0.01;
+
+fn nudged(d: time::Date, days: f64) -> time::Date {
+    time::Date::from_julian(d.julian() + days)
+}
+
+/// Angle between two objects in degrees
+fn separation(a: &CelObj, b: &CelObj, rf: &RefFrame) -> Result<f64, Error> {
+    Ok(query::of_date(a, rf)?
+        .dist(query::of_date(b, rf)?)
+        .degrees())
+}
+
+/// How fast two objects are moving apart, in a form that rises through zero when they're closest
+fn widening(a: &CelObj, b: &CelObj, rf: &RefFrame) -> Result<f64, Error> {
+    let at = |days| RefFrame {
+        date: nudged(rf.date, days),
+        ..*rf
+    };
+    Ok(separation(a, b, &at(NUDGE))? - separation(a, b, &at(-NUDGE))?)
+}
+
+fn longitude(obj: &CelObj, rf: &RefFrame) -> Result<time::Angle, Error> {
+    Ok(query::of_date(obj, rf)?.ecliptic(rf.date).0)
+}
+
+/// How far east of the sun an object is in ecliptic longitude
+fn from_sun(obj: &CelObj, rf: &RefFrame) -> Result<time::Angle, Error> {
+    Ok(longitude(obj, rf)? - longitude(&CelObj::Sun, rf)?)
+}
+
+/// Every aspect of a planet, minor planet, or comet between the date and `end`
+///
+/// Conjunctions and oppositions are in ecliptic longitude, and stations are where the motion in
+/// longitude changes direction.
+pub fn aspects(
+    obj: &CelObj,
+    rf: &RefFrame,
+    end: time::Date,
+) -> Result<Vec<(time::Date, Aspect)>, Error> {
+    if !matches!(obj, CelObj::Planet(_) | CelObj::Minor(_)) {
+        return Err(Error::Unsupported(
+            "Aspects".to_string(),
+            "anything but a planet, minor planet, or comet",
+        ));
+    }
+    let at = |d: time::Date| RefFrame { date: d, ..*rf };
+    let motion = |d| {
+        Ok(
+            (longitude(obj, &at(nudged(d, NUDGE)))? - longitude(obj, &at(nudged(d, -NUDGE)))?)
+                .sin(),
+        )
+    };
+    // Planets nearer the sun than the observer have inferior and superior conjunctions and greatest
+    // elongations instead of oppositions
+    let observer = rf.from.unwrap_or(&sol::EARTH).locationcart(rf.date);
+    let inner = query::heliocentric(obj, rf.date).is_some_and(|(x, y, z)| {
+        x * x + y * y + z * z < observer.0.powi(2) + observer.1.powi(2) + observer.2.powi(2)
+    });
+
+    let mut found = vec![];
+    for c in crossings(|d| Ok(from_sun(obj, &at(d))?.sin()), rf.date, end, 1.0)? {
+        let aspect = if from_sun(obj, &at(c.date))?.cos() < 0.0 {
+            Aspect::Opposition
+        } else if !inner {
+            Aspect::Conjunction
+        } else if separation(obj, &CelObj::Sun, &at(c.date))? < 90.0
+            && distance(obj, &at(c.date))? < distance(&CelObj::Sun, &at(c.date))?
+        {
+            Aspect::InferiorConjunction
+        } else {
+            Aspect::SuperiorConjunction
+        };
+        found.push((c.date, aspect));
+    }
+    if inner {
+        for c in crossings(|d| widening(obj, &CelObj::Sun, &at(d)), rf.date, end, 1.0)? {
+            if !c.rising {
+                let east = from_sun(obj, &at(c.date))?.sin() > 0.0;
+                found.push((
+                    c.date,
+                    if east {
+                        Aspect::GreatestEast
+                    } else {
+                        Aspect::GreatestWest
+                    },
+                ));
+            }
+        }
+    }
+    for c in crossings(motion, rf.date, end, 1.0)? {
+        found.push((
+            c.date,
+            if c.rising {
+                Aspect::StationDirect
+            } else {
+                Aspect::StationRetrograde
+            },
+        ));
+    }
+    found.sort_by(|a, b| a.0.julian().total_cmp(&b.0.julian()));
+    Ok(found)
+}
+
+/// Distance of an object in AU
+fn distance(obj: &CelObj, rf: &RefFrame) -> Result<f64, Error> {
+    match property_of(obj, Property::Distance, rf)? {
+        Value::Dist(d) => Ok(d),
+        _ => unreachable!(),
+    }
+}
+
+/// The next aspect of a planet, or the next time it reaches the one asked for
+pub fn next_aspect(
+    obj: &CelObj,
+    rf: &RefFrame,
+    asked: Option<Aspect>,
+) -> Result<Option<(time::Date, Aspect)>, Error> {
+    let end = nudged(rf.date, SYNODIC_WINDOW);
+    Ok(aspects(obj, rf, end)?
+        .into_iter()
+        .find(|(_, a)| asked.is_none_or(|x| a.is(x))))
+}
+
+/// Every time two objects are closest together between the date and `end`
+pub fn conjunctions(
+    a: &CelObj,
+    b: &CelObj,
+    rf: &RefFrame,
+    end: time::Date,
+) -> Result<Vec<time::Date>, Error> {
+    let moving = |o: &CelObj| {
+        matches!(
+            o,
+            CelObj::Sun
+                | CelObj::Moon
+                | CelObj::Planet(_)
+                | CelObj::Minor(_)
+                | CelObj::PlanetMoon(_)
+        )
+    };
+    if matches!(a, CelObj::Satellite(_)) || matches!(b, CelObj::Satellite(_)) {
+        return Err(Error::Unsupported(
+            "Conjunctions".to_string(),
+            "a satellite",
+        ));
+    }
+    if !moving(a) && !moving(b) {
+        return Err(Error::Unsupported(
+            "Conjunctions".to_string(),
+            "two objects fixed on the sky",
+        ));
+    }
+    // Moons of planets can pass theirs twice a day, and the moon passes everything once a month
+    let step = match (a, b) {
+        (CelObj::PlanetMoon(_), _) | (_, CelObj::PlanetMoon(_)) => HOURLY,
+        (CelObj::Moon, _) | (_, CelObj::Moon) => 0.25,
+        _ => 1.0,
+    };
+    let at = |d: time::Date| RefFrame { date: d, ..*rf };
+    // Where they pass north or south of each other, rather than where one turns back short of the other
+    let passing = |d: time::Date| -> Result<bool, Error> {
+        let (la, ba) = query::of_date(a, &at(d))?.ecliptic(d);
+        let (lb, bb) = query::of_date(b, &at(d))?.ecliptic(d);
+        let across = (la - lb).to_latitude().degrees().abs() * ba.cos();
+        Ok(across <= (ba - bb).to_latitude().degrees().abs())
+    };
+    let mut found = vec![];
+    for c in crossings(|d| widening(a, b, &at(d)), rf.date, end, step)? {
+        if c.rising && passing(c.date)? {
+            found.push(c.date);
+        }
+    }
+    Ok(found)
+}
+
+/// The next time two objects are closest together
+pub fn next_conjunction(
+    a: &CelObj,
+    b: &CelObj,
+    rf: &RefFrame,
+) -> Result<Option<time::Date>, Error> {
+    let end = nudged(rf.date, SYNODIC_WINDOW);
+    Ok(conjunctions(a, b, rf, end)?.first().copied())
+}
+
+/// Ways of dividing the year by the apparent ecliptic longitude of the sun
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Terms {
+    /// The equinoxes and solstices, every 90°
+    Seasons,
+    /// The seasons and the cross-quarter days halfway between them, every 45°
+    CrossQuarter,
+    /// The 24 solar terms of the Chinese calendar, every 15°
+    Solar,
+}
+impl Terms {
+    /// Degrees of longitude between one term and the next
+    fn step(self) -> f64 {
+        match self {
+            Terms::Seasons => 90.0,
+            Terms::CrossQuarter => 45.0,
+            Terms::Solar => 15.0,
+        }
+    }
+}
+
+/// Names of the solar terms, every 15° from the March equinox
+const SOLAR_TERMS: [&str; 24] = [
+    "Chunfen",
+    "Qingming",
+    "Guyu",
+    "Lixia",
+    "Xiaoman",
+    "Mangzhong",
+    "Xiazhi",
+    "Xiaoshu",
+    "Dashu",
+    "Liqiu",
+    "Chushu",
+    "Bailu",
+    "Qiufen",
+    "Hanlu",
+    "Shuangjiang",
+    "Lidong",
+    "Xiaoxue",
+    "Daxue",
+    "Dongzhi",
+    "Xiaohan",
+    "Dahan",
+    "Lichun",
+    "Yushui",
+    "Jingzhe",
+];
+
+/// A point the sun passes each year, named as it is in one of the [`Terms`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Term {
+    pub terms: Terms,
+    /// Apparent ecliptic longitude of the sun in degrees
+    pub longitude: f64,
+}
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let i = (self.longitude / 15.0).round() as usize % 24;
+        if self.terms == Terms::Solar {
+            return write!(f, "{}", SOLAR_TERMS[i]);
+        }
+        write!(
+            f,
+            "{}",
+            // Named for the month they fall in, so they're the same in both hemispheres
+            match i / 3 {
+                0 => "March Equinox",
+                1 => "May Cross-Quarter",
+                2 => "June Solstice",
+                3 => "August Cross-Quarter",
+                4 => "September Equinox",
+                5 => "November Cross-Quarter",
+                6 => "December Solstice",
+                _ => "February Cross-Quarter",
+            }
+        )
+    }
+}
+
+/// A little over a year, the longest it takes the sun to pass through every term, in days
+const YEAR: f64 = 367.0;
+
+/// Apparent ecliptic longitude of the sun on the equinox of the date
+fn apparent_sun(d: time::Date) -> time::Angle {
+    time::Angle::from_degrees(lunar::sun(d).0 + lunar::nutation(d))
+}
+
+/// Every time the sun reaches one of the terms between `start` and `end`, by its longitude on the
+/// equinox of the date, seen from the center of the earth
+pub fn terms(
+    terms: Terms,
+    start: time::Date,
+    end: time::Date,
+) -> Result<Vec<(time::Date, Term)>, Error> {
+    let step = terms.step();
+    // Crosses zero at every multiple of the step
+    let f = |d| Ok((apparent_sun(d) * (180.0 / step)).sin());
+    crossings(f, start, end, 1.0)?
+        .into_iter()
+        .map(|c| {
+            let l = apparent_sun(c.date).degrees();
+            let longitude = ((l / step).round() * step) % 360.0;
+            Ok((c.date, Term { terms, longitude }))
+        })
+        .collect()
+}
+
+/// The next time the sun reaches one of the terms
+pub fn next_term(terms: Terms, rf: &RefFrame) -> Result<(time::Date, Term), Error> {
+    let end = nudged(rf.date, YEAR);
+    Ok(self::terms(terms, rf.date, end)?
+        .into_iter()
+        .next()
+        .expect("The sun passes every term in a year"))
+}
+
+/// Altitude of the object in degrees, geometric (no refraction)
+pub fn altitude(obj: &CelObj, rf: &RefFrame) -> Result<f64, Error> {
+    let (lat, long, _) = rf.latlong.ok_or(Error::MissingLocation)?;
+    let Value::Crd(p, _) = property_of(obj, Property::Equatorial, rf)? else {
+        unreachable!();
+    };
+    Ok(sidereal::horizon(p, rf.date, lat, long)
+        .1
+        .to_latitude()
+        .degrees())
+}
+
+/// Hour angle of the object, the angle west of the meridian
+pub fn hour_angle(obj: &CelObj, rf: &RefFrame) -> Result<time::Angle, Error> {
+    let (_, long, _) = rf.latlong.ok_or(Error::MissingLocation)?;
+    let Value::Crd(p, _) = property_of(obj, Property::Equatorial, rf)? else {
+        unreachable!();
+    };
+    Ok(sidereal::lst(rf.date, long) - p.equatorial().0)
+}
+
+/// The geometric altitude in degrees at which the upper limb of the object is on the horizon
+///
+/// This is the refraction at the horizon, less the semidiameter of the sun, moon and planets.
+/// Positions are already topocentric, so parallax doesn't need to be accounted for here.
+pub fn horizon_altitude(obj: &CelObj, rf: &RefFrame) -> f64 {
+    let d = rf.date;
+    -rf.refraction.horizon()
+        - match obj {
+            CelObj::Sun => sol::SUN.angdia(d).degrees() / 2.0,
+            CelObj::Moon => moon::MOON.angdia(d).degrees() / 2.0,
+            CelObj::Planet(p) => p.angdia(d).degrees() / 2.0,
+            _ => 0.0,
+        }
+}
+
+fn rise_or_set(obj: &CelObj, rf: &RefFrame, rising: bool) -> Result<Option<time::Date>, Error> {
+    let above_horizon = |d: time::Date| {
+        let rf = RefFrame { date: d, ..*rf };
+        Ok(altitude(obj, &rf)? - horizon_altitude(obj, &rf))
+    };
+    next_crossing(
+        above_horizon,
+        rf.date,
+        window_end(rf),
+        sample_step(obj),
+        rising,
+    )
+}
+
+/// The next time the center of the object crosses `alt` degrees of geometric altitude, going up if `rising`
+pub fn next_altitude(
+    obj: &CelObj,
+    rf: &RefFrame,
+    alt: f64,
+    rising: bool,
+) -> Result<Option<time::Date>, Error> {
+    let above = |d: time::Date| Ok(altitude(obj, &RefFrame { date: d, ..*rf })? - alt);
+    next_crossing(above, rf.date, window_end(rf), sample_step(obj), rising)
+}
+
+/// The next time the upper limb of the object rises above the horizon
+pub fn next_rise(obj: &CelObj, rf: &RefFrame) -> Result<Option<time::Date>, Error> {
+    rise_or_set(obj, rf, true)
+}
+
+/// The next time the upper limb of the object sets below the horizon
+pub fn next_set(obj: &CelObj, rf: &RefFrame) -> Result<Option<time::Date>, Error> {
+    rise_or_set(obj, rf, false)
+}
+
+/// The next time the object crosses the meridian, above the pole if `upper`, and below it if not
+pub fn next_transit(obj: &CelObj, rf: &RefFrame, upper: bool) -> Result<Option<time::Date>, Error> {
+    // The sine of the hour angle goes from negative to positive at upper transit
+    let west = |d: time::Date| Ok(hour_angle(obj, &RefFrame { date: d, ..*rf })?.sin());
+    next_crossing(west, rf.date, window_end(rf), sample_step(obj), upper)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Midnight UT at a site, with standard refraction
+    fn site(lat: f64, long: f64, (y, m, d): (i64, u8, u8)) -> RefFrame {
+        RefFrame {
+            latlong: Some((
+                time::Angle::from_degrees(lat),
+                time::Angle::from_degrees(long),
+                0.0,
+            )),
+            date: time::Date::from_calendar(y, m, d, time::Angle::from_turns(0.0)),
+            refraction: Refraction::Standard,
+            from: None,
+        }
+    }
+
+    /// Checks that an event is on the day and within a minute of the time, in UT
+    fn at(found: Option<time::Date>, (m, d): (u8, u8), (h, min): (f64, f64)) {
+        let (_, fm, fd, t) = found.expect("No event found").calendar();
+        assert_eq!((fm, fd), (m, d));
+        assert!(
+            (t.decimal() - h - min / 60.0).abs() < 1.0 / 60.0,
+            "{}",
+            t.decimal()
+        );
+    }
+
+    #[test]
+    fn london_solstice_sun() {
+        // 04:43 and 21:21 BST
+        let rf = site(51.5074, -0.1278, (2025, 6, 21));
+        at(next_rise(&CelObj::Sun, &rf).unwrap(), (6, 21), (3.0, 43.0));
+        at(next_set(&CelObj::Sun, &rf).unwrap(), (6, 21), (20.0, 21.0));
+    }
+
+    #[test]
+    fn meeus_venus_boston() {
+        // Example 15.a, rising at 12h25m and setting at 2h55m, both on March 20
+        let rf = site(42.3333, -71.0833, (1988, 3, 20));
+        let venus = CelObj::Planet(sol::VENUS);
+        at(next_rise(&venus, &rf).unwrap(), (3, 20), (12.0, 25.0));
+        at(next_set(&venus, &rf).unwrap(), (3, 20), (2.0, 55.0));
+    }
+
+    #[test]
+    fn meeus_venus_transit() {
+        // Example 15.a, transit at 19h40m30s
+        let rf = site(42.3333, -71.0833, (1988, 3, 20));
+        at(
+            next_transit(&CelObj::Planet(sol::VENUS), &rf, true).unwrap(),
+            (3, 20),
+            (19.0, 40.5),
+        );
+    }
+
+    #[test]
+    fn aspects_2025() {
+        let rf = RefFrame {
+            latlong: None,
+            ..site(0.0, 0.0, (2025, 1, 1))
+        };
+        // Mars is opposite the sun in longitude at 02:32 UT on January 16
+        let (d, _) = next_aspect(&CelObj::Planet(sol::MARS), &rf, Some(Aspect::Opposition))
+            .unwrap()
+            .expect("No opposition");
+        let (_, m, dd, t) = d.calendar();
+        assert_eq!((m, dd), (1, 16));
+        assert!(
+            (t.decimal() - (2.0 + 32.0 / 60.0)).abs() < 10.0 / 60.0,
+            "{}",
+            t.decimal()
+        );
+        // Venus is farthest east of the sun on January 10, 47.2° from it
+        let venus = CelObj::Planet(sol::VENUS);
+        let (d, _) = next_aspect(&venus, &rf, Some(Aspect::GreatestEast))
+            .unwrap()
+            .expect("No greatest elongation");
+        let (_, m, dd, _) = d.calendar();
+        assert_eq!((m, dd), (1, 10));
+        let Value::Ang(elong, _) =
+            property_of(&venus, Property::Elongation, &RefFrame { date: d, ..rf }).unwrap()
+        else {
+            panic!("No elongation");
+        };
+        assert!((elong.degrees() - 47.2).abs() < 0.05, "{}", elong.degrees());
+    }
+
+    #[test]
+    fn seasons_2025() {
+        // Published by the US Naval Observatory, to the minute
+        let start = time::Date::from_calendar(2025, 1, 1, time::Angle::from_turns(0.0));
+        let end = time::Date::from_calendar(2026, 1, 1, time::Angle::from_turns(0.0));
+        let found = terms(Terms::Seasons, start, end).unwrap();
+        let expected = [
+            (3, 20, 9.0 + 1.0 / 60.0),
+            (6, 21, 2.0 + 42.0 / 60.0),
+            (9, 22, 18.0 + 19.0 / 60.0),
+            (12, 21, 15.0 + 3.0 / 60.0),
+        ];
+        assert_eq!(found.len(), expected.len());
+        for ((d, term), (month, day, hours)) in found.iter().zip(expected) {
+            let (_, m, dd, t) = d.calendar();
+            assert_eq!((m, dd), (month, day), "{term}");
+            assert!((t.decimal() - hours).abs() < 1.0 / 60.0, "{term}");
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use crate::constellation::precess;
+use pracstro::{coord, time};
+
+type Vector = (f64, f64, f64);
+
+/// Rows are the galactic x (towards the center), y (towards l = 90°), and z (north pole) axes,
+/// on J2000 equatorial axes (Hipparcos, Vol. 1, Sec. 1.5.3)
+const GALACTIC: [Vector; 3] = [
+    (-0.054_875_560_4, -0.873_437_090_2, -0.483_835_015_5),
+    (0.494_109_427_9, -0.444_829_630_0, 0.746_982_244_5),
+    (-0.867_666_149_0, -0.198_076_373_4, 0.455_983_776_2),
+];
+
+/// Galactic longitude and latitude of the supergalactic north pole, and of the origin of supergalactic longitude
+/// (de Vaucouleurs et al. 1976)
+const SUPERGALACTIC_POLE: (f64, f64) = (47.37, 6.32);
+const SUPERGALACTIC_ORIGIN: (f64, f64) = (137.37, 0.0);
+
+fn dot(a: Vector, b: Vector) -> f64 {
+    a.0 * b.0 + a.1 * b.1 + a.2 * b.2
+}
+
+/// Unit vector towards a longitude and latitude
+fn towards(long: time::Angle, lat: time::Angle) -> Vector {
+    (lat.cos() * long.cos(), lat.cos() * long.sin(), lat.sin())
+}
+
+/// Longitude and latitude of a vector, on the axes given by three unit vectors
+///
+/// The latitude is found with the arctangent, since the matrix is only orthogonal to ten digits,
+/// which the arcsine would turn into an arcsecond near the poles.
+fn spherical(v: Vector, axes: [Vector; 3]) -> (time::Angle, time::Angle) {
+    let (x, y, z) = (dot(v, axes[0]), dot(v, axes[1]), dot(v, axes[2]));
+    (time::Angle::atan2(y, x), time::Angle::atan2(z, x.hypot(y)))
+}
+
+/// Galactic direction of equatorial coordinates in the equinox of `epoch`
+fn galactic_vector(c: coord::Coord, epoch: time::Date) -> Vector {
+    let (ra, de) = precess(c, epoch, time::J2000).equatorial();
+    let v = towards(ra, de);
+    (
+        dot(v, GALACTIC[0]),
+        dot(v, GALACTIC[1]),
+        dot(v, GALACTIC[2]),
+    )
+}
+
+/// Galactic longitude and latitude (l, b) of equatorial coordinates in the equinox of `epoch`
+pub fn galactic(c: coord::Coord, epoch: time::Date) -> (time::Angle, time::Angle) {
+    spherical(
+        galactic_vector(c, epoch),
+        [(1.0, 0.0, 0.0), (0.0, 1.0, 0.0), (0.0, 0.0, 1.0)],
+    )
+}
+
+/// Supergalactic longitude and latitude (SGL, SGB) of equatorial coordinates in the equinox of `epoch`
+pub fn supergalactic(c: coord::Coord, epoch: time::Date) -> (time::Angle, time::Angle) {
+    let deg = time::Angle::from_degrees;
+    let z = towards(deg(SUPERGALACTIC_POLE.0), deg(SUPERGALACTIC_POLE.1));
+    let x = towards(deg(SUPERGALACTIC_ORIGIN.0), deg(SUPERGALACTIC_ORIGIN.1));
+    let y = (
+        z.1 * x.2 - z.2 * x.1,
+        z.2 * x.0 - z.0 * x.2,
+        z.0 * x.1 - z.1 * x.0,
+    );
+    spherical(galactic_vector(c, epoch), [x, y, z])
+}
+
+/// J2000 equatorial coordinates of a galactic longitude and latitude
+pub fn from_galactic(l: time::Angle, b: time::Angle) -> coord::Coord {
+    let v = towards(l, b);
+    // The matrix is a rotation, so its transpose is its inverse
+    let x = v.0 * GALACTIC[0].0 + v.1 * GALACTIC[1].0 + v.2 * GALACTIC[2].0;
+    let y = v.0 * GALACTIC[0].1 + v.1 * GALACTIC[1].1 + v.2 * GALACTIC[2].1;
+    let z = v.0 * GALACTIC[0].2 + v.1 * GALACTIC[1].2 + v.2 * GALACTIC[2].2;
+    coord::Coord::from_cartesian(x, y, z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deg(c: (time::Angle, time::Angle)) -> (f64, f64) {
+        (c.0.degrees(), c.1.to_latitude().degrees())
+    }
+
+    fn j2000(ra: f64, de: f64) -> coord::Coord {
+        coord::Coord::from_equatorial(time::Angle::from_degrees(ra), time::Angle::from_degrees(de))
+    }
+
+    #[test]
+    fn sirius_and_vega() {
+        // As given by SIMBAD
+        let (l, b) = deg(galactic(j2000(101.28715539, -16.71611582), time::J2000));
+        assert!((l - 227.2303).abs() < 0.0002 && (b + 8.8903).abs() < 0.0002);
+        let (l, b) = deg(galactic(j2000(279.23473479, 38.78368896), time::J2000));
+        assert!((l - 67.4482).abs() < 0.0002 && (b - 19.2373).abs() < 0.0002);
+    }
+
+    #[test]
+    fn poles() {
+        // The north galactic pole is at 12h51m26.28s +27°07′41.7″
+        let (_, b) = deg(galactic(j2000(192.85948, 27.12825), time::J2000));
+        assert!((b - 90.0).abs() < 0.00002);
+        let pole = from_galactic(
+            time::Angle::from_degrees(47.37),
+            time::Angle::from_degrees(6.32),
+        );
+        let (_, sgb) = deg(supergalactic(pole, time::J2000));
+        assert!((sgb - 90.0).abs() < 1e-6);
+        let origin = from_galactic(
+            time::Angle::from_degrees(137.37),
+            time::Angle::from_degrees(0.0),
+        );
+        let (sgl, sgb) = deg(supergalactic(origin, time::J2000));
+        assert!(sgl.abs() < 1e-6 && sgb.abs() < 1e-6);
+    }
+}
@@ -0,0 +1,73 @@
+/*!
+# deskephem - Modular CLI Astronomy
+
+The engine behind the `deskephem` command, usable as a library.
+
+A query is an object from the [`catalog`], a list of [`Property`]s, and a reference frame
+(date and optional observer location). [`run`] returns one typed [`Value`] per property,
+which can be matched on directly or formatted with [`Display`](std::fmt::Display)
+(human readable) or the alternate `{:#}` form (raw numbers, as used by the JSON and CSV drivers).
+
+```
+use deskephem::*;
+use pracstro::time;
+
+let cat = catalog::read();
+let date = time::Date::from_calendar(2025, 4, 1, time::Angle::default());
+let rf = RefFrame {
+    latlong: parse::latlong("40n,95w").unwrap(),
+    date,
+    refraction: Refraction::Standard,
+    from: None,
+};
+
+let r = run(&cat["moon"], &[Property::Horizontal, Property::IllumFrac], &rf).unwrap();
+if let Value::Crd(c, CrdView::Horizontal(rf)) = &r[0] {
+    let (lat, long, _) = rf.latlong.unwrap();
+    let (_azi, _alt) = sidereal::horizon(*c, rf.date, lat, long);
+}
+```
+*/
+
+/// Handles the reading and querying of the catalog of celestial objects
+pub mod catalog;
+/// Which constellation a position is in
+pub mod constellation;
+/// Solar and lunar eclipses
+pub mod eclipse;
+/// Errors from parsing and running queries
+pub mod error;
+/// Searching for the instants events happen, like rising and setting
+pub mod event;
+/// Galactic and supergalactic coordinates
+pub mod galactic;
+/// Positions of the moon and sun, more accurate than pracstro's
+pub mod lunar;
+/// Minor planets and comets, moving on orbits from their elements
+pub mod minor;
+/// The moons of Jupiter and Saturn
+pub mod moons;
+/// Stars hidden behind the moon and planets
+pub mod occultation;
+/// Output drivers for the CLI (plain text, CSV, JSON)
+pub mod output;
+/// Parsing of dates, angles, objects and properties from strings
+pub mod parse;
+/// Computing properties of objects
+pub mod query;
+/// Earth satellites, from two-line elements
+pub mod sat;
+/// Sidereal time, and turning equatorial coordinates into horizontal ones
+pub mod sidereal;
+/// pracstro provides a way to do this, but that isn't functional in a lot of contexts
+///
+/// Used in ephemeris generation and date reading
+pub mod timestep;
+/// The types queries are made of and return
+pub mod value;
+
+pub use error::Error;
+pub use query::{property_of, run, Airmass, Center, Plane, Property};
+pub use value::{
+    AngView, CelObj, CrdView, DistUnit, Location, PhaseView, RefFrame, Refraction, Value,
+};
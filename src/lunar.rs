@@ -0,0 +1,463 @@
+use crate::{query, sidereal, value::RefFrame};
+use pracstro::{coord, time};
+use std::f64::consts::PI;
+
+const KM_PER_AU: f64 = 149_597_870.7;
+const EARTH_RADIUS: f64 = 6378.14; // Kilometers
+
+/// Periodic terms for the longitude and distance of the moon: multiples of D, M, M′, F, and the
+/// coefficients in millionths of a degree and thousandths of a kilometer (Meeus, Astronomical
+/// Algorithms, Table 47.A)
+const LONGITUDE_DISTANCE: [(f64, f64, f64, f64, f64, f64); 60] = [
+    (0.0, 0.0, 1.0, 0.0, 6288774.0, -20905355.0),
+    (2.0, 0.0, -1.0, 0.0, 1274027.0, -3699111.0),
+    (2.0, 0.0, 0.0, 0.0, 658314.0, -2955968.0),
+    (0.0, 0.0, 2.0, 0.0, 213618.0, -569925.0),
+    (0.0, 1.0, 0.0, 0.0, -185116.0, 48888.0),
+    (0.0, 0.0, 0.0, 2.0, -114332.0, -3149.0),
+    (2.0, 0.0, -2.0, 0.0, 58793.0, 246158.0),
+    (2.0, -1.0, -1.0, 0.0, 57066.0, -152138.0),
+    (2.0, 0.0, 1.0, 0.0, 53322.0, -170733.0),
+    (2.0, -1.0, 0.0, 0.0, 45758.0, -204586.0),
+    (0.0, 1.0, -1.0, 0.0, -40923.0, -129620.0),
+    (1.0, 0.0, 0.0, 0.0, -34720.0, 108743.0),
+    (0.0, 1.0, 1.0, 0.0, -30383.0, 104755.0),
+    (2.0, 0.0, 0.0, -2.0, 15327.0, 10321.0),
+    (0.0, 0.0, 1.0, 2.0, -12528.0, 0.0),
+    (0.0, 0.0, 1.0, -2.0, 10980.0, 79661.0),
+    (4.0, 0.0, -1.0, 0.0, 10675.0, -34782.0),
+    (0.0, 0.0, 3.0, 0.0, 10034.0, -23210.0),
+    (4.0, 0.0, -2.0, 0.0, 8548.0, -21636.0),
+    (2.0, 1.0, -1.0, 0.0, -7888.0, 24208.0),
+    (2.0, 1.0, 0.0, 0.0, -6766.0, 30824.0),
+    (1.0, 0.0, -1.0, 0.0, -5163.0, -8379.0),
+    (1.0, 1.0, 0.0, 0.0, 4987.0, -16675.0),
+    (2.0, -1.0, 1.0, 0.0, 4036.0, -12831.0),
+    (2.0, 0.0, 2.0, 0.0, 3994.0, -10445.0),
+    (4.0, 0.0, 0.0, 0.0, 3861.0, -11650.0),
+    (2.0, 0.0, -3.0, 0.0, 3665.0, 14403.0),
+    (0.0, 1.0, -2.0, 0.0, -2689.0, -7003.0),
+    (2.0, 0.0, -1.0, 2.0, -2602.0, 0.0),
+    (2.0, -1.0, -2.0, 0.0, 2390.0, 10056.0),
+    (1.0, 0.0, 1.0, 0.0, -2348.0, 6322.0),
+    (2.0, -2.0, 0.0, 0.0, 2236.0, -9884.0),
+    (0.0, 1.0, 2.0, 0.0, -2120.0, 5751.0),
+    (0.0, 2.0, 0.0, 0.0, -2069.0, 0.0),
+    (2.0, -2.0, -1.0, 0.0, 2048.0, -4950.0),
+    (2.0, 0.0, 1.0, -2.0, -1773.0, 4130.0),
+    (2.0, 0.0, 0.0, 2.0, -1595.0, 0.0),
+    (4.0, -1.0, -1.0, 0.0, 1215.0, -3958.0),
+    (0.0, 0.0, 2.0, 2.0, -1110.0, 0.0),
+    (3.0, 0.0, -1.0, 0.0, -892.0, 3258.0),
+    (2.0, 1.0, 1.0, 0.0, -810.0, 2616.0),
+    (4.0, -1.0, -2.0, 0.0, 759.0, -1897.0),
+    (0.0, 2.0, -1.0, 0.0, -713.0, -2117.0),
+    (2.0, 2.0, -1.0, 0.0, -700.0, 2354.0),
+    (2.0, 1.0, -2.0, 0.0, 691.0, 0.0),
+    (2.0, -1.0, 0.0, -2.0, 596.0, 0.0),
+    (4.0, 0.0, 1.0, 0.0, 549.0, -1423.0),
+    (0.0, 0.0, 4.0, 0.0, 537.0, -1117.0),
+    (4.0, -1.0, 0.0, 0.0, 520.0, -1571.0),
+    (1.0, 0.0, -2.0, 0.0, -487.0, -1739.0),
+    (2.0, 1.0, 0.0, -2.0, -399.0, 0.0),
+    (0.0, 0.0, 2.0, -2.0, -381.0, -4421.0),
+    (1.0, 1.0, 1.0, 0.0, 351.0, 0.0),
+    (3.0, 0.0, -2.0, 0.0, -340.0, 0.0),
+    (4.0, 0.0, -3.0, 0.0, 330.0, 0.0),
+    (2.0, -1.0, 2.0, 0.0, 327.0, 0.0),
+    (0.0, 2.0, 1.0, 0.0, -323.0, 1165.0),
+    (1.0, 1.0, -1.0, 0.0, 299.0, 0.0),
+    (2.0, 0.0, 3.0, 0.0, 294.0, 0.0),
+    (2.0, 0.0, -1.0, -2.0, 0.0, 8752.0),
+];
+
+/// Periodic terms for the latitude of the moon, in millionths of a degree (Meeus, Table 47.B)
+const LATITUDE: [(f64, f64, f64, f64, f64); 60] = [
+    (0.0, 0.0, 0.0, 1.0, 5128122.0),
+    (0.0, 0.0, 1.0, 1.0, 280602.0),
+    (0.0, 0.0, 1.0, -1.0, 277693.0),
+    (2.0, 0.0, 0.0, -1.0, 173237.0),
+    (2.0, 0.0, -1.0, 1.0, 55413.0),
+    (2.0, 0.0, -1.0, -1.0, 46271.0),
+    (2.0, 0.0, 0.0, 1.0, 32573.0),
+    (0.0, 0.0, 2.0, 1.0, 17198.0),
+    (2.0, 0.0, 1.0, -1.0, 9266.0),
+    (0.0, 0.0, 2.0, -1.0, 8822.0),
+    (2.0, -1.0, 0.0, -1.0, 8216.0),
+    (2.0, 0.0, -2.0, -1.0, 4324.0),
+    (2.0, 0.0, 1.0, 1.0, 4200.0),
+    (2.0, 1.0, 0.0, -1.0, -3359.0),
+    (2.0, -1.0, -1.0, 1.0, 2463.0),
+    (2.0, -1.0, 0.0, 1.0, 2211.0),
+    (2.0, -1.0, -1.0, -1.0, 2065.0),
+    (0.0, 1.0, -1.0, -1.0, -1870.0),
+    (4.0, 0.0, -1.0, -1.0, 1828.0),
+    (0.0, 1.0, 0.0, 1.0, -1794.0),
+    (0.0, 0.0, 0.0, 3.0, -1749.0),
+    (0.0, 1.0, -1.0, 1.0, -1565.0),
+    (1.0, 0.0, 0.0, 1.0, -1491.0),
+    (0.0, 1.0, 1.0, 1.0, -1475.0),
+    (0.0, 1.0, 1.0, -1.0, -1410.0),
+    (0.0, 1.0, 0.0, -1.0, -1344.0),
+    (1.0, 0.0, 0.0, -1.0, -1335.0),
+    (0.0, 0.0, 3.0, 1.0, 1107.0),
+    (4.0, 0.0, 0.0, -1.0, 1021.0),
+    (4.0, 0.0, -1.0, 1.0, 833.0),
+    (0.0, 0.0, 1.0, -3.0, 777.0),
+    (4.0, 0.0, -2.0, 1.0, 671.0),
+    (2.0, 0.0, 0.0, -3.0, 607.0),
+    (2.0, 0.0, 2.0, -1.0, 596.0),
+    (2.0, -1.0, 1.0, -1.0, 491.0),
+    (2.0, 0.0, -2.0, 1.0, -451.0),
+    (0.0, 0.0, 3.0, -1.0, 439.0),
+    (2.0, 0.0, 2.0, 1.0, 422.0),
+    (2.0, 0.0, -3.0, -1.0, 421.0),
+    (2.0, 1.0, -1.0, 1.0, -366.0),
+    (2.0, 1.0, 0.0, 1.0, -351.0),
+    (4.0, 0.0, 0.0, 1.0, 331.0),
+    (2.0, -1.0, 1.0, 1.0, 315.0),
+    (2.0, -2.0, 0.0, -1.0, 302.0),
+    (0.0, 0.0, 1.0, 3.0, -283.0),
+    (2.0, 1.0, 1.0, -1.0, -229.0),
+    (1.0, 1.0, 0.0, -1.0, 223.0),
+    (1.0, 1.0, 0.0, 1.0, 223.0),
+    (0.0, 1.0, -2.0, -1.0, -220.0),
+    (2.0, 1.0, -1.0, -1.0, -220.0),
+    (1.0, 0.0, 1.0, 1.0, -185.0),
+    (2.0, -1.0, -2.0, -1.0, 181.0),
+    (0.0, 1.0, 2.0, 1.0, -177.0),
+    (4.0, 0.0, -2.0, -1.0, 176.0),
+    (4.0, -1.0, -1.0, -1.0, 166.0),
+    (1.0, 0.0, 1.0, -1.0, -164.0),
+    (4.0, 0.0, 1.0, -1.0, 132.0),
+    (1.0, 0.0, -1.0, -1.0, -119.0),
+    (4.0, -1.0, 0.0, -1.0, 115.0),
+    (2.0, -2.0, 0.0, 1.0, 107.0),
+];
+
+/// Periodic terms for the heliocentric longitude of the earth from VSOP87, each an amplitude in
+/// hundred millionths of a radian, a phase in radians, and a frequency in radians per millennium
+/// (Meeus, Appendix III). The series for each power of the time go from `EARTH_L0` to `EARTH_L5`
+const EARTH_L0: [(f64, f64, f64); 64] = [
+    (175347046.0, 0.0, 0.0),
+    (3341656.0, 4.6692568, 6283.07585),
+    (34894.0, 4.6261, 12566.1517),
+    (3497.0, 2.7441, 5753.3849),
+    (3418.0, 2.8289, 3.5231),
+    (3136.0, 3.6277, 77713.7715),
+    (2676.0, 4.4181, 7860.4194),
+    (2343.0, 6.1352, 3930.2097),
+    (1324.0, 0.7425, 11506.7698),
+    (1273.0, 2.0371, 529.691),
+    (1199.0, 1.1096, 1577.3435),
+    (990.0, 5.233, 5884.927),
+    (902.0, 2.045, 26.298),
+    (857.0, 3.508, 398.149),
+    (780.0, 1.179, 5223.694),
+    (753.0, 2.533, 5507.553),
+    (505.0, 4.583, 18849.228),
+    (492.0, 4.205, 775.523),
+    (357.0, 2.92, 0.067),
+    (317.0, 5.849, 11790.629),
+    (284.0, 1.899, 796.298),
+    (271.0, 0.315, 10977.079),
+    (243.0, 0.345, 5486.778),
+    (206.0, 4.806, 2544.314),
+    (205.0, 1.869, 5573.143),
+    (202.0, 2.458, 6069.777),
+    (156.0, 0.833, 213.299),
+    (132.0, 3.411, 2942.463),
+    (126.0, 1.083, 20.775),
+    (115.0, 0.645, 0.98),
+    (103.0, 0.636, 4694.003),
+    (102.0, 0.976, 15720.839),
+    (102.0, 4.267, 7.114),
+    (99.0, 6.21, 2146.17),
+    (98.0, 0.68, 155.42),
+    (86.0, 5.98, 161000.69),
+    (85.0, 1.3, 6275.96),
+    (85.0, 3.67, 71430.7),
+    (80.0, 1.81, 17260.15),
+    (79.0, 3.04, 12036.46),
+    (75.0, 1.76, 5088.63),
+    (74.0, 3.5, 3154.69),
+    (74.0, 4.68, 801.82),
+    (70.0, 0.83, 9437.76),
+    (62.0, 3.98, 8827.39),
+    (61.0, 1.82, 7084.9),
+    (57.0, 2.78, 6286.6),
+    (56.0, 4.39, 14143.5),
+    (56.0, 3.47, 6279.55),
+    (52.0, 0.19, 12139.55),
+    (52.0, 1.33, 1748.02),
+    (51.0, 0.28, 5856.48),
+    (49.0, 0.49, 1194.45),
+    (41.0, 5.37, 8429.24),
+    (41.0, 2.4, 19651.05),
+    (39.0, 6.17, 10447.39),
+    (37.0, 6.04, 10213.29),
+    (37.0, 2.57, 1059.38),
+    (36.0, 1.71, 2352.87),
+    (36.0, 1.78, 6812.77),
+    (33.0, 0.59, 17789.85),
+    (30.0, 0.44, 83996.85),
+    (30.0, 2.74, 1349.87),
+    (25.0, 3.16, 4690.48),
+];
+const EARTH_L1: [(f64, f64, f64); 34] = [
+    (628331966747.0, 0.0, 0.0),
+    (206059.0, 2.678235, 6283.07585),
+    (4303.0, 2.6351, 12566.1517),
+    (425.0, 1.59, 3.523),
+    (119.0, 5.796, 26.298),
+    (109.0, 2.966, 1577.344),
+    (93.0, 2.59, 18849.23),
+    (72.0, 1.14, 529.69),
+    (68.0, 1.87, 398.15),
+    (67.0, 4.41, 5507.55),
+    (59.0, 2.89, 5223.69),
+    (56.0, 2.17, 155.42),
+    (45.0, 0.4, 796.3),
+    (36.0, 0.47, 775.52),
+    (29.0, 2.65, 7.11),
+    (21.0, 5.34, 0.98),
+    (19.0, 1.85, 5486.78),
+    (19.0, 4.97, 213.3),
+    (17.0, 2.99, 6275.96),
+    (16.0, 0.03, 2544.31),
+    (16.0, 1.43, 2146.17),
+    (15.0, 1.21, 10977.08),
+    (12.0, 2.83, 1748.02),
+    (12.0, 3.26, 5088.63),
+    (12.0, 5.27, 1194.45),
+    (12.0, 2.08, 4694.0),
+    (11.0, 0.77, 553.57),
+    (10.0, 1.3, 6286.6),
+    (10.0, 4.24, 1349.87),
+    (9.0, 2.7, 242.73),
+    (9.0, 5.64, 951.72),
+    (8.0, 5.3, 2352.87),
+    (6.0, 2.65, 9437.76),
+    (6.0, 4.67, 4690.48),
+];
+const EARTH_L2: [(f64, f64, f64); 20] = [
+    (52919.0, 0.0, 0.0),
+    (8720.0, 1.0721, 6283.0758),
+    (309.0, 0.867, 12566.152),
+    (27.0, 0.05, 3.52),
+    (16.0, 5.19, 26.3),
+    (16.0, 3.68, 155.42),
+    (10.0, 0.76, 18849.23),
+    (9.0, 2.06, 77713.77),
+    (7.0, 0.83, 775.52),
+    (5.0, 4.66, 1577.34),
+    (4.0, 1.03, 7.11),
+    (4.0, 3.44, 5573.14),
+    (3.0, 5.14, 796.3),
+    (3.0, 6.05, 5507.55),
+    (3.0, 1.19, 242.73),
+    (3.0, 6.12, 529.69),
+    (3.0, 0.31, 398.15),
+    (3.0, 2.28, 553.57),
+    (2.0, 4.38, 5223.69),
+    (2.0, 3.75, 0.98),
+];
+const EARTH_L3: [(f64, f64, f64); 7] = [
+    (289.0, 5.844, 6283.076),
+    (35.0, 0.0, 0.0),
+    (17.0, 5.49, 12566.15),
+    (3.0, 5.2, 155.42),
+    (1.0, 4.72, 3.52),
+    (1.0, 5.3, 18849.23),
+    (1.0, 5.97, 242.73),
+];
+const EARTH_L4: [(f64, f64, f64); 3] = [
+    (114.0, PI, 0.0),
+    (8.0, 4.13, 6283.08),
+    (1.0, 3.84, 12566.15),
+];
+const EARTH_L5: [(f64, f64, f64); 1] = [(1.0, PI, 0.0)];
+
+/// Difference between terrestrial and universal time in days, from the fits of Espenak and Meeus
+pub fn delta_t(d: time::Date) -> f64 {
+    let y = 2000.0 + (d.julian() - time::J2000.julian()) / 365.25;
+    let seconds = match y {
+        2005.0..2050.0 => {
+            let t = y - 2000.0;
+            62.92 + 0.32217 * t + 0.005589 * t * t
+        }
+        1986.0..2005.0 => {
+            let t = y - 2000.0;
+            63.86 + 0.3345 * t - 0.060374 * t * t
+                + 0.0017275 * t.powi(3)
+                + 0.000651814 * t.powi(4)
+                + 0.00002373599 * t.powi(5)
+        }
+        _ => {
+            let u = (y - 1820.0) / 100.0;
+            -20.0 + 32.0 * u * u
+        }
+    };
+    seconds / 86400.0
+}
+
+/// Julian centuries of terrestrial time since J2000
+fn centuries(d: time::Date) -> f64 {
+    (d.julian() + delta_t(d) - time::J2000.julian()) / 36525.0
+}
+
+/// Mean anomaly of the sun in degrees
+fn sun_anomaly(t: f64) -> f64 {
+    357.5291092 + 35999.0502909 * t - 0.0001536 * t * t + t.powi(3) / 24490000.0
+}
+
+/// Apparent geocentric ecliptic longitude and latitude of the moon in degrees, less nutation, and
+/// its distance in kilometers
+///
+/// From the main terms of ELP-2000/82 (Meeus, Ch. 47), good to about 10″. pracstro's moon is only
+/// good to a fraction of a degree, which would put eclipses tens of minutes off.
+pub fn moon(d: time::Date) -> (f64, f64, f64) {
+    let t = centuries(d);
+    let rad = |x: f64| x.to_radians();
+
+    let lp = 218.3164477 + 481267.88123421 * t - 0.0015786 * t * t + t.powi(3) / 538841.0
+        - t.powi(4) / 65194000.0;
+    let dd = 297.8501921 + 445267.1114034 * t - 0.0018819 * t * t + t.powi(3) / 545868.0
+        - t.powi(4) / 113065000.0;
+    let m = sun_anomaly(t);
+    let mp = 134.9634114 + 477198.8675055 * t + 0.0087414 * t * t + t.powi(3) / 69699.0
+        - t.powi(4) / 14712000.0;
+    let f = 93.2720950 + 483202.0175233 * t - 0.0036539 * t * t - t.powi(3) / 3526000.0
+        + t.powi(4) / 863310000.0;
+    let a1 = 119.75 + 131.849 * t;
+    let a2 = 53.09 + 479264.290 * t;
+    let a3 = 313.45 + 481266.484 * t;
+    // Terms with the sun's anomaly shrink as the earth's orbit gets rounder
+    let e = 1.0 - 0.002516 * t - 0.0000074 * t * t;
+    let arg = |cd: f64, cm: f64, cmp: f64, cf: f64| rad(cd * dd + cm * m + cmp * mp + cf * f);
+
+    let (sigma_l, sigma_r) =
+        LONGITUDE_DISTANCE
+            .iter()
+            .fold((0.0, 0.0), |(sl, sr), &(cd, cm, cmp, cf, l, r)| {
+                let x = arg(cd, cm, cmp, cf);
+                let k = e.powi(cm.abs() as i32);
+                (sl + l * k * x.sin(), sr + r * k * x.cos())
+            });
+    let sigma_b: f64 = LATITUDE
+        .iter()
+        .map(|&(cd, cm, cmp, cf, b)| b * e.powi(cm.abs() as i32) * arg(cd, cm, cmp, cf).sin())
+        .sum();
+
+    let long = lp
+        + (sigma_l + 3958.0 * rad(a1).sin() + 1962.0 * rad(lp - f).sin() + 318.0 * rad(a2).sin())
+            / 1_000_000.0;
+    let lat = (sigma_b - 2235.0 * rad(lp).sin()
+        + 382.0 * rad(a3).sin()
+        + 175.0 * rad(a1 - f).sin()
+        + 175.0 * rad(a1 + f).sin()
+        + 127.0 * rad(lp - mp).sin()
+        - 115.0 * rad(lp + mp).sin())
+        / 1_000_000.0;
+    (long, lat, 385000.56 + sigma_r / 1000.0)
+}
+
+/// Apparent geocentric ecliptic longitude of the sun in degrees, less nutation, and its distance in AU
+///
+/// The longitude is from the truncated VSOP87 series (Meeus, Ch. 25), good to about a second of arc.
+pub fn sun(d: time::Date) -> (f64, f64) {
+    let t = centuries(d);
+    let tau = t / 10.0;
+    let rad = |x: f64| x.to_radians();
+    let series = |terms: &[(f64, f64, f64)]| -> f64 {
+        terms.iter().map(|(a, b, c)| a * (b + c * tau).cos()).sum()
+    };
+    let l = [
+        &EARTH_L0[..],
+        &EARTH_L1,
+        &EARTH_L2,
+        &EARTH_L3,
+        &EARTH_L4,
+        &EARTH_L5,
+    ]
+    .iter()
+    .rev()
+    .fold(0.0, |acc, terms| acc * tau + series(terms))
+        / 100_000_000.0;
+
+    let m = sun_anomaly(t);
+    let c = (1.914602 - 0.004817 * t - 0.000014 * t * t) * rad(m).sin()
+        + (0.019993 - 0.000101 * t) * rad(2.0 * m).sin()
+        + 0.000289 * rad(3.0 * m).sin();
+    let ecc = 0.016708634 - 0.000042037 * t - 0.0000001267 * t * t;
+    let dist = 1.000001018 * (1.0 - ecc * ecc) / (1.0 + ecc * rad(m + c).cos());
+
+    // Opposite the earth, on the FK5 system, and behind by the aberration of light
+    let long = l.to_degrees() + 180.0 - 0.09033 / 3600.0 - 20.4898 / 3600.0 / dist;
+    (long.rem_euclid(360.0), dist)
+}
+
+/// Nutation in longitude in degrees, good to about half a second of arc (Meeus, Ch. 22)
+pub fn nutation(d: time::Date) -> f64 {
+    let t = centuries(d);
+    let rad = |x: f64| x.to_radians();
+    let omega = rad(125.04452 - 1934.136261 * t);
+    let l = rad(280.4665 + 36000.7698 * t);
+    let lp = rad(218.3165 + 481267.8813 * t);
+    (-17.20 * omega.sin() - 1.32 * (2.0 * l).sin() - 0.23 * (2.0 * lp).sin()
+        + 0.21 * (2.0 * omega).sin())
+        / 3600.0
+}
+
+/// Equatorial coordinates of the moon on the equinox of the date, corrected for the parallax of the
+/// observer if there is one, and its distance from them in kilometers
+pub fn topocentric(rf: &RefFrame) -> (coord::Coord, f64) {
+    let deg = time::Angle::from_degrees;
+    let (long, lat, dist) = moon(rf.date);
+    let c = query::topocentric(
+        coord::Coord::from_ecliptic(deg(long), deg(lat), rf.date),
+        dist / KM_PER_AU,
+        rf,
+    );
+    match (rf.latlong, rf.from) {
+        // Nearer by about an earth radius when it's overhead
+        (Some((la, lo, _)), None) => {
+            let (_, alt) = sidereal::horizon(c, rf.date, la, lo);
+            (c, dist - EARTH_RADIUS * alt.sin())
+        }
+        _ => (c, dist),
+    }
+}
+
+/// Equatorial coordinates of the sun on the equinox of the date
+pub fn sun_position(d: time::Date) -> coord::Coord {
+    coord::Coord::from_ecliptic(
+        time::Angle::from_degrees(sun(d).0),
+        time::Angle::default(),
+        d,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The universal time of an instant of terrestrial time
+    fn ut(jde: f64) -> time::Date {
+        time::Date::from_julian(jde - delta_t(time::Date::from_julian(jde)))
+    }
+
+    #[test]
+    fn meeus_sun() {
+        // Example 25.b, 1992 October 13.0 TD: 199°54′21.818″ with nutation
+        let d = ut(2_448_908.5);
+        let long = sun(d).0 + nutation(d);
+        assert!((long - (199.0 + 54.0 / 60.0 + 21.818 / 3600.0)).abs() < 1.0 / 3600.0);
+    }
+
+    #[test]
+    fn meeus_nutation() {
+        // Example 22.a, 1987 April 10.0 TD: −3.788″
+        assert!((nutation(ut(2_446_895.5)) * 3600.0 + 3.788).abs() < 0.5);
+    }
+}
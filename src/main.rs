@@ -1,81 +1,25 @@
+use deskephem::*;
 use pracstro::time;
-use value::*;
 
-/// Handles the reading and querying of the catalog of celestial objects
-pub mod catalog;
-pub mod output;
-pub mod parse;
-pub mod query;
-pub mod value;
-
-/// pracstro provides a way to do this, but that isn't functional in a lot of contexts
-///
-/// Used in ephemeris generation and date reading
-mod timestep {
-    use chrono::prelude::*;
-    use pracstro::time;
-    #[derive(Copy, Clone, Debug, PartialEq)]
-    /// Most things can be represented as seconds or months
-    /// * 1 second: 1 second
-    /// * 1 minute: 60 seconds
-    /// * 1 hour: 3600 seconds
-    /// * 1 day: 86400 seconds
-    /// * 1 week: 604800 seconds
-    /// * 1 month: 1 month
-    /// * 1 year: 12 months
-    pub enum Step {
-        S(f64),
-        M(chrono::Months),
-    }
-    pub fn step_forward_date(d: time::Date, s: Step) -> time::Date {
-        match s {
-            Step::S(sec) => time::Date::from_julian(d.julian() + (sec.abs() / 86400.0)),
-            Step::M(m) => time::Date::from_unix(
-                (DateTime::from_timestamp(d.unix() as i64, 0).unwrap() + m).timestamp() as f64,
-            ),
-        }
-    }
-    pub fn step_back_date(d: time::Date, s: Step) -> time::Date {
-        match s {
-            Step::S(sec) => time::Date::from_julian(d.julian() - (sec.abs() / 86400.0)),
-            Step::M(m) => time::Date::from_unix(
-                (DateTime::from_timestamp(d.unix() as i64, 0).unwrap() - m).timestamp() as f64,
-            ),
-        }
-    }
-    pub struct EphemIter {
-        now: time::Date,
-        step: Step,
-        end: time::Date,
-    }
-    impl EphemIter {
-        pub fn new(start: time::Date, step: Step, end: time::Date) -> EphemIter {
-            EphemIter {
-                now: start,
-                step,
-                end,
-            }
-        }
-    }
-    impl Iterator for EphemIter {
-        type Item = time::Date;
+/// Prints the error and exits with its exit code
+fn fail(e: &Error) -> ! {
+    eprintln!("deskephem: {e}");
+    std::process::exit(e.exit_code())
+}
 
-        fn next(&mut self) -> Option<Self::Item> {
-            if self.now.julian() < self.end.julian() {
-                let s = self.now;
-                self.now = step_forward_date(self.now, self.step);
-                Some(s)
-            } else {
-                None
-            }
-        }
+/// Adds objects read from a file to the catalog, warning about any skipped for their names
+fn merge(cat: &mut catalog::Catalog, path: &str, kind: &str, found: Vec<(String, CelObj)>) {
+    let taken = catalog::merge(cat, found);
+    if let Some(name) = taken.first() {
+        eprintln!(
+            "deskephem: {path}: skipped {} {kind} with names already in use, like \"{name}\"",
+            taken.len()
+        );
     }
 }
 
 fn main() {
     use clap::{arg, command};
-    let cat = catalog::read();
-    let ccheck = cat.clone();
     let matches = command!()
     	.help_template("{before-help}{name} ({version}) - {about-with-newline}\n{usage-heading} {usage}\n\n{all-args}{after-help}\n\nWritten by {author}")
         .arg(
@@ -84,21 +28,81 @@ fn main() {
                 .default_value("now"),
         )
         .arg(
-            arg!(-l --latlong ["Latitude,Longitude"] "Set the latitude/longitude")
+            arg!(-l --latlong ["Latitude,Longitude[,Elevation]"] "Set the latitude/longitude, and optionally elevation in meters")
                 .value_parser(parse::latlong)
                 .default_value("none"),
         )
+        .arg(
+            arg!(-R --refraction ["none|standard|Temperature,Pressure"] "Set the refraction model, temperature in °C and pressure in hPa")
+                .value_parser(parse::refraction)
+                .default_value("standard"),
+        )
+        .arg(
+            arg!(--from [Planet] "View the sky from another planet")
+                .value_parser(parse::observer)
+                .default_value("earth")
+                .conflicts_with("latlong"),
+        )
         .arg(arg!(-E --ephem ["Start,Step,End"] "Generates Table").value_parser(parse::ephemq))
         .arg(
             arg!(-T --format [Format] "Output Format")
                 .value_parser(["term", "csv", "json"])
                 .default_value("term"),
         )
-        .arg(arg!([object] "Celestial Object").required(true).value_parser(move |s: &str| parse::object(s, &ccheck)))
-        .arg(arg!([properties] ... "Properties").required(true).value_parser(move |s: &str| parse::property(s, &cat)))
-        .get_matches();
-    let location = *matches.get_one("latlong").unwrap();
-    let date = *matches.get_one("date").unwrap();
+        .arg(arg!(--catalog <File> "Load stars from a CSV, Hipparcos, or Yale Bright Star catalog").action(clap::ArgAction::Append))
+        .arg(arg!(--elements <File> "Load minor planets and comets from an MPC orbital elements file").action(clap::ArgAction::Append))
+        .arg(arg!(--tle <File> "Load earth satellites from a two or three line element file").action(clap::ArgAction::Append))
+        .arg(arg!(--passes <End> "List passes of a satellite until the date").value_parser(parse::date))
+        .arg(arg!(--eclipses <End> "List eclipses of the sun or moon until the date").value_parser(parse::date).conflicts_with("passes"))
+        .arg(arg!(--events <End> "List oppositions, conjunctions, elongations and stations of a planet until the date").value_parser(parse::date).conflicts_with_all(["passes", "eclipses"]))
+        .arg(arg!(--occultations <End> "List stars hidden by the moon or a planet until the date").value_parser(parse::date).conflicts_with_all(["passes", "eclipses", "events"]))
+        .arg(arg!(--seasons <Year> "List the equinoxes and solstices of the year").value_parser(clap::value_parser!(i64)).conflicts_with_all(["passes", "eclipses", "events", "occultations"]))
+        .arg(
+            arg!(--terms <Terms> "List cross-quarter days or the 24 solar terms along with the seasons")
+                .value_parser(["crossquarter", "solar"])
+                .requires("seasons"),
+        )
+        .arg(arg!(--survey "List everything in the catalogs that's up, instead of one object").conflicts_with_all(["passes", "eclipses", "events", "occultations", "seasons", "object"]))
+        .arg(
+            arg!(--"min-alt" <Degrees> "Leave out objects lower than this in the survey")
+                .value_parser(clap::value_parser!(f64))
+                .allow_negative_numbers(true)
+                .default_value("0")
+                .requires("survey"),
+        )
+        .arg(
+            arg!(--"mag-limit" <Magnitude> "Leave out objects fainter than this in the survey")
+                .value_parser(clap::value_parser!(f64))
+                .allow_negative_numbers(true)
+                .requires("survey"),
+        )
+        .arg(
+            arg!(--sort <Column> "Sort the survey by a column")
+                .value_parser(clap::builder::EnumValueParser::<query::SortBy>::new())
+                .default_value("alt")
+                .requires("survey"),
+        )
+        .arg(arg!(--with <Object> "List conjunctions with another object instead").requires("events"))
+        // Objects and properties are parsed once the catalog is fully loaded
+        .arg(arg!([object] "Celestial Object").required_unless_present("survey"))
+        .arg(arg!([properties] ... "Properties").required_unless_present_any(["passes", "eclipses", "events", "occultations", "seasons", "survey"]))
+        .try_get_matches()
+        .unwrap_or_else(|e| {
+            // Errors from our value parsers get their own exit codes, the rest are usage errors
+            match std::error::Error::source(&e).and_then(|x| x.downcast_ref::<Error>()) {
+                Some(x) => {
+                    let _ = e.print();
+                    std::process::exit(x.exit_code())
+                }
+                None => e.exit(),
+            }
+        });
+    let rf = RefFrame {
+        latlong: *matches.get_one("latlong").unwrap(),
+        date: *matches.get_one("date").unwrap(),
+        refraction: *matches.get_one("refraction").unwrap(),
+        from: *matches.get_one("from").unwrap(),
+    };
     let formatter = match matches.get_one::<String>("format").unwrap().as_str() {
         "term" => output::TERM,
         "csv" => output::CSV,
@@ -106,28 +110,145 @@ fn main() {
         _ => todo!(),
     };
 
-    let obj = matches.get_one::<CelObj>("object").unwrap().clone();
+    // Built in objects take priority over ones with the same name in files, then earlier files over later ones
+    let mut cat = catalog::read();
+    let star_files = catalog::config_catalogs().into_iter().chain(
+        matches
+            .get_many::<String>("catalog")
+            .unwrap_or_default()
+            .cloned(),
+    );
+    for path in star_files {
+        let found = catalog::read_stars(&path).unwrap_or_else(|e| fail(&e));
+        merge(&mut cat, &path, "star(s)", found);
+    }
+    for path in matches.get_many::<String>("elements").unwrap_or_default() {
+        let found = catalog::read_elements(path).unwrap_or_else(|e| fail(&e));
+        merge(&mut cat, path, "minor planet(s) or comet(s)", found);
+    }
+    for path in matches.get_many::<String>("tle").unwrap_or_default() {
+        let found = catalog::read_tle(path).unwrap_or_else(|e| fail(&e));
+        merge(&mut cat, path, "satellite(s)", found);
+    }
+
+    if matches.get_flag("survey") {
+        let rows = query::survey(
+            &cat,
+            &rf,
+            *matches.get_one("min-alt").unwrap(),
+            matches.get_one("mag-limit").copied(),
+            *matches.get_one("sort").unwrap(),
+        )
+        .unwrap_or_else(|e| fail(&e));
+        (formatter.start)();
+        (formatter.nameheader)(&query::SURVEY_COLUMNS);
+        rows.into_iter()
+            .for_each(|(name, r)| (formatter.namedq)(r, &query::SURVEY_COLUMNS, &name));
+        (formatter.footer)();
+        return;
+    }
+
+    let obj = parse::object(matches.get_one::<String>("object").unwrap(), &cat)
+        .unwrap_or_else(|e| fail(&e));
+
+    if let Some(year) = matches.get_one::<i64>("seasons") {
+        let terms = match matches.get_one::<String>("terms").map(String::as_str) {
+            Some("crossquarter") => event::Terms::CrossQuarter,
+            Some("solar") => event::Terms::Solar,
+            _ => event::Terms::Seasons,
+        };
+        let rows = query::seasons(&obj, &rf, *year, terms).unwrap_or_else(|e| fail(&e));
+        let columns = query::season_columns(terms);
+        (formatter.start)();
+        (formatter.propheader)(&columns);
+        rows.into_iter()
+            .for_each(|(date, r)| (formatter.ephemq)(r, &columns, date));
+        (formatter.footer)();
+        return;
+    }
+    if let Some(end) = matches.get_one::<time::Date>("occultations") {
+        let stars = occultation::stars(&cat);
+        let rows = query::occultations(&obj, &stars, &rf, *end).unwrap_or_else(|e| fail(&e));
+        let columns = query::occultation_columns(&stars, &rf);
+        (formatter.start)();
+        (formatter.propheader)(&columns);
+        rows.into_iter()
+            .for_each(|(date, r)| (formatter.ephemq)(r, &columns, date));
+        (formatter.footer)();
+        return;
+    }
+    if let Some(end) = matches.get_one::<time::Date>("events") {
+        let (rows, columns) = match matches.get_one::<String>("with") {
+            Some(s) => {
+                let other = parse::object(s, &cat).unwrap_or_else(|e| fail(&e));
+                (
+                    query::conjunctions(&obj, &other, &rf, *end),
+                    vec![query::Property::AngBet(other)],
+                )
+            }
+            None => (
+                query::aspects(&obj, &rf, *end),
+                query::ASPECT_COLUMNS.to_vec(),
+            ),
+        };
+        let rows = rows.unwrap_or_else(|e| fail(&e));
+        (formatter.start)();
+        (formatter.propheader)(&columns);
+        rows.into_iter()
+            .for_each(|(date, r)| (formatter.ephemq)(r, &columns, date));
+        (formatter.footer)();
+        return;
+    }
+    if let Some(end) = matches.get_one::<time::Date>("eclipses") {
+        let rows = query::eclipses(&obj, &rf, *end).unwrap_or_else(|e| fail(&e));
+        let columns = query::eclipse_columns(&obj, &rf);
+        (formatter.start)();
+        (formatter.propheader)(&columns);
+        rows.into_iter()
+            .for_each(|(date, r)| (formatter.ephemq)(r, &columns, date));
+        (formatter.footer)();
+        return;
+    }
+    if let Some(end) = matches.get_one::<time::Date>("passes") {
+        let rows = query::passes(&obj, &rf, *end).unwrap_or_else(|e| fail(&e));
+        (formatter.start)();
+        (formatter.propheader)(&query::PASS_COLUMNS);
+        rows.into_iter()
+            .for_each(|(date, r)| (formatter.ephemq)(r, &query::PASS_COLUMNS, date));
+        (formatter.footer)();
+        return;
+    }
+
     let propl: Vec<query::Property> = matches
-        .get_many::<query::Property>("properties")
-        .unwrap()
-        .cloned()
+        .get_many::<String>("properties")
+        .unwrap_or_default()
+        .map(|s| parse::property(s, &cat).unwrap_or_else(|e| fail(&e)))
         .collect();
 
-    let q = |loc: Location, d: time::Date| {
-        query::run(&obj, &propl, loc, d).unwrap_or_else(|x| panic!("Failed to parse query: {x}"))
+    let q = |d: time::Date| {
+        query::run(&obj, &propl, &RefFrame { date: d, ..rf }).unwrap_or_else(|e| fail(&e))
     };
 
+    let ephem = matches.get_one::<(time::Date, timestep::Step, time::Date)>("ephem");
+
+    // Check the query before any output is written, so a bad query doesn't leave half a table
+    let first = RefFrame {
+        date: ephem.map_or(rf.date, |x| x.0),
+        ..rf
+    };
+    if let Err(e) = query::run(&obj, &propl, &first) {
+        fail(&e);
+    }
+
     (formatter.start)();
 
-    if let Some((start, step, end)) =
-        matches.get_one::<(time::Date, timestep::Step, time::Date)>("ephem")
-    {
+    if let Some((start, step, end)) = ephem {
         (formatter.propheader)(&propl);
         timestep::EphemIter::new(*start, *step, *end)
-            .map(|date| (q(location, date), date))
+            .map(|date| (q(date), date))
             .for_each(|(r, date)| (formatter.ephemq)(r, &propl, date))
     } else {
-        (formatter.query)(q(location, date));
+        (formatter.query)(q(rf.date));
     }
 
     (formatter.footer)();
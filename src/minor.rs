@@ -0,0 +1,163 @@
+use pracstro::{coord, sol, time};
+
+/// The Gaussian gravitational constant, in radians per day
+const GAUSS_K: f64 = 0.017_202_098_95;
+
+/// Obliquity of the ecliptic at J2000, the frame orbital elements are given in
+const OBLIQUITY_J2000: f64 = 23.439_291_1;
+
+/// How the brightness of the body changes with distance and phase
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MagLaw {
+    /// Asteroids: absolute magnitude H and slope parameter G (Bowell et al., 1989)
+    HG(f64, f64),
+    /// Comets: total absolute magnitude g and activity parameter k, m = g + 5 log Δ + 2.5k log r
+    Comet(f64, f64),
+}
+
+/// Time of perihelion of an orbit with semimajor axis `a` AU, and mean anomaly `m` at `epoch`
+///
+/// Asteroid elements are given this way, while comets give the time of perihelion directly
+pub fn perihelion_time(a: f64, m: time::Angle, epoch: time::Date) -> time::Date {
+    time::Date::from_julian(epoch.julian() - m.to_latitude().radians() / (GAUSS_K / a.powf(1.5)))
+}
+
+/// A minor planet or comet, moving on a two-body orbit around the sun
+///
+/// Elements are in the J2000 ecliptic frame. Elliptic, parabolic and hyperbolic orbits are all handled.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MinorBody {
+    /// Perihelion distance, AU
+    pub q: f64,
+    /// Eccentricity
+    pub e: f64,
+    /// Inclination
+    pub i: time::Angle,
+    /// Longitude of the ascending node
+    pub node: time::Angle,
+    /// Argument of perihelion
+    pub peri: time::Angle,
+    /// Time of perihelion passage
+    pub tp: time::Date,
+    pub mag: MagLaw,
+}
+
+impl MinorBody {
+    /// Position in the plane of the orbit, with perihelion along the x axis, in AU
+    fn orbitplane(&self, d: time::Date) -> (f64, f64) {
+        let t = d.julian() - self.tp.julian();
+        let (q, e) = (self.q, self.e);
+        if (e - 1.0).abs() < 1e-6 {
+            // Parabolic, Barker's equation
+            let w = 3.0 * GAUSS_K / (2.0 * q * q * q).sqrt() * t;
+            let y = (w / 2.0 + (w * w / 4.0 + 1.0).sqrt()).cbrt();
+            let s = y - 1.0 / y;
+            (q * (1.0 - s * s), 2.0 * q * s)
+        } else if e < 1.0 {
+            let a = q / (1.0 - e);
+            let m = (GAUSS_K / a.powf(1.5) * t) % std::f64::consts::TAU;
+            let mut ee = if e < 0.8 { m } else { std::f64::consts::PI };
+            for _ in 0..100 {
+                let de = (ee - e * ee.sin() - m) / (1.0 - e * ee.cos());
+                ee -= de;
+                if de.abs() < 1e-12 {
+                    break;
+                }
+            }
+            (a * (ee.cos() - e), a * (1.0 - e * e).sqrt() * ee.sin())
+        } else {
+            let a = q / (e - 1.0);
+            let m = GAUSS_K / a.powf(1.5) * t;
+            let mut h = m.signum() * (2.0 * m.abs() / e + 1.8).ln();
+            for _ in 0..100 {
+                let dh = (e * h.sinh() - h - m) / (e * h.cosh() - 1.0);
+                h -= dh;
+                if dh.abs() < 1e-12 {
+                    break;
+                }
+            }
+            (a * (e - h.cosh()), a * (e * e - 1.0).sqrt() * h.sinh())
+        }
+    }
+
+    /// Heliocentric position in AU, in the J2000 equatorial frame, like [`sol::Planet::locationcart`]
+    pub fn locationcart(&self, d: time::Date) -> (f64, f64, f64) {
+        let (xp, yp) = self.orbitplane(d);
+        let r = (xp * xp + yp * yp).sqrt();
+        let u = time::Angle::atan2(yp, xp) + self.peri;
+        let (o, i) = (self.node, self.i);
+
+        let x = r * (o.cos() * u.cos() - o.sin() * u.sin() * i.cos());
+        let y = r * (o.sin() * u.cos() + o.cos() * u.sin() * i.cos());
+        let z = r * (u.sin() * i.sin());
+
+        let eps = time::Angle::from_degrees(OBLIQUITY_J2000);
+        (
+            x,
+            eps.cos() * y - eps.sin() * z,
+            eps.sin() * y + eps.cos() * z,
+        )
+    }
+
+    /// Position relative to the earth, corrected for light time
+    fn geocart(&self, d: time::Date) -> (f64, f64, f64) {
+        const LIGHT_DAY_PER_AU: f64 = 0.005_775_518_3;
+        let e = sol::EARTH.locationcart(d);
+        let mut tau = 0.0;
+        let mut g = (0.0, 0.0, 0.0);
+        for _ in 0..3 {
+            let c = self.locationcart(time::Date::from_julian(d.julian() - tau));
+            g = (c.0 - e.0, c.1 - e.1, c.2 - e.2);
+            tau = (g.0 * g.0 + g.1 * g.1 + g.2 * g.2).sqrt() * LIGHT_DAY_PER_AU;
+        }
+        g
+    }
+
+    /// Geocentric equatorial coordinates at the date
+    pub fn location(&self, d: time::Date) -> coord::Coord {
+        let (x, y, z) = self.geocart(d);
+        coord::Coord::from_cartesian(x, y, z).precess(time::J2000, d)
+    }
+
+    /// Distance from the earth in AU
+    pub fn distance(&self, d: time::Date) -> f64 {
+        let (x, y, z) = self.geocart(d);
+        (x * x + y * y + z * z).sqrt()
+    }
+
+    /// Distance from the sun in AU
+    pub fn sun_distance(&self, d: time::Date) -> f64 {
+        let (x, y, z) = self.locationcart(d);
+        (x * x + y * y + z * z).sqrt()
+    }
+
+    /// The sun-body-earth angle
+    fn sunangle(&self, d: time::Date) -> time::Angle {
+        let (r, delta, big_r) = (self.sun_distance(d), self.distance(d), sol::SUN.distance(d));
+        time::Angle::acos(
+            ((r * r + delta * delta - big_r * big_r) / (2.0 * r * delta)).clamp(-1.0, 1.0),
+        )
+    }
+
+    /// Phase angle, in the same convention as [`sol::Planet::phaseangle`], where 180° is full
+    pub fn phaseangle(&self, d: time::Date) -> time::Angle {
+        time::Angle::from_degrees(180.0) - self.sunangle(d)
+    }
+
+    pub fn magnitude(&self, d: time::Date) -> f64 {
+        self.brightness(self.sun_distance(d), self.distance(d), self.sunangle(d))
+    }
+
+    /// Magnitude at `r` AU from the sun and `delta` AU from the observer, with the sun-body-observer angle
+    pub fn brightness(&self, r: f64, delta: f64, sunangle: time::Angle) -> f64 {
+        match self.mag {
+            MagLaw::HG(h, g) => {
+                let tb = (sunangle.radians() / 2.0).tan();
+                let phi1 = (-3.33 * tb.powf(0.63)).exp();
+                let phi2 = (-1.87 * tb.powf(1.22)).exp();
+                h + 5.0 * (r * delta).log10() - 2.5 * ((1.0 - g) * phi1 + g * phi2).log10()
+            }
+            MagLaw::Comet(g, k) => g + 5.0 * delta.log10() + 2.5 * k * r.log10(),
+        }
+    }
+}
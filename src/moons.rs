@@ -0,0 +1,382 @@
+use pracstro::{coord, sol, time};
+
+const KM_PER_AU: f64 = 149_597_870.7;
+
+/// Light time, in days per AU
+const LIGHT_DAY_PER_AU: f64 = 0.005_775_518_3;
+
+type Vector = (f64, f64, f64);
+
+fn dot(a: Vector, b: Vector) -> f64 {
+    a.0 * b.0 + a.1 * b.1 + a.2 * b.2
+}
+fn cross(a: Vector, b: Vector) -> Vector {
+    (
+        a.1 * b.2 - a.2 * b.1,
+        a.2 * b.0 - a.0 * b.2,
+        a.0 * b.1 - a.1 * b.0,
+    )
+}
+fn scale(a: Vector, k: f64) -> Vector {
+    (a.0 * k, a.1 * k, a.2 * k)
+}
+fn sub(a: Vector, b: Vector) -> Vector {
+    (a.0 - b.0, a.1 - b.1, a.2 - b.2)
+}
+fn unit(a: Vector) -> Vector {
+    scale(a, 1.0 / dot(a, a).sqrt())
+}
+/// Unit vector towards a J2000 right ascension and declination in degrees
+fn towards(ra: f64, de: f64) -> Vector {
+    let (ra, de) = (ra.to_radians(), de.to_radians());
+    (de.cos() * ra.cos(), de.cos() * ra.sin(), de.sin())
+}
+
+fn sin(x: f64) -> f64 {
+    x.to_radians().sin()
+}
+fn cos(x: f64) -> f64 {
+    x.to_radians().cos()
+}
+
+/// A planet with moons
+struct Parent {
+    planet: sol::Planet,
+    /// Equatorial radius, km
+    radius: f64,
+    flattening: f64,
+    /// Right ascension and declination of the north pole, J2000
+    pole: (f64, f64),
+}
+
+const JUPITER: Parent = Parent {
+    planet: sol::JUPITER,
+    radius: 71_492.0,
+    flattening: 0.064_87,
+    pole: (268.057, 64.495),
+};
+
+const SATURN: Parent = Parent {
+    planet: sol::SATURN,
+    radius: 60_268.0,
+    flattening: 0.097_96,
+    pole: (40.589, 83.537),
+};
+
+/// The ascending node of Saturn's equator on the B1950 ecliptic, the origin of the longitudes of its moons
+const SATURN_NODE: (f64, f64) = (170.3585, 4.1538);
+
+/// The things that can happen to a moon, or its shadow, and the disk of its planet
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Phenomenon {
+    /// The moon crosses in front of the planet
+    Transit,
+    /// The shadow of the moon falls on the planet
+    ShadowTransit,
+    /// The moon is hidden behind the planet
+    Occultation,
+    /// The moon is in the shadow of the planet
+    Eclipse,
+}
+
+/// The Galilean moons of Jupiter and the major moons of Saturn
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PlanetMoon {
+    Io,
+    Europa,
+    Ganymede,
+    Callisto,
+    Mimas,
+    Enceladus,
+    Tethys,
+    Dione,
+    Rhea,
+    Titan,
+}
+
+impl PlanetMoon {
+    fn is_jovian(self) -> bool {
+        matches!(
+            self,
+            PlanetMoon::Io | PlanetMoon::Europa | PlanetMoon::Ganymede | PlanetMoon::Callisto
+        )
+    }
+
+    fn parent(self) -> &'static Parent {
+        match self.is_jovian() {
+            true => &JUPITER,
+            false => &SATURN,
+        }
+    }
+
+    /// The planet the moon orbits
+    pub fn planet(self) -> sol::Planet {
+        self.parent().planet.clone()
+    }
+
+    /// Radius in km, and absolute magnitude V(1,0)
+    fn physical(self) -> (f64, f64) {
+        match self {
+            PlanetMoon::Io => (1821.6, -1.68),
+            PlanetMoon::Europa => (1560.8, -1.41),
+            PlanetMoon::Ganymede => (2634.1, -2.09),
+            PlanetMoon::Callisto => (2410.3, -1.05),
+            PlanetMoon::Mimas => (198.2, 3.3),
+            PlanetMoon::Enceladus => (252.1, 2.1),
+            PlanetMoon::Tethys => (531.1, 0.7),
+            PlanetMoon::Dione => (561.4, 0.8),
+            PlanetMoon::Rhea => (763.8, 0.1),
+            PlanetMoon::Titan => (2574.7, -1.28),
+        }
+    }
+
+    /// Position of a Galilean moon in the plane of Jupiter's equator
+    ///
+    /// Gives the angle from the direction of the earth, and the distance in Jupiter radii.
+    /// Meeus, Astronomical Algorithms, Ch. 44 (the lower accuracy method)
+    fn jovian(self, d: time::Date) -> (f64, f64) {
+        let d = d.julian() - time::J2000.julian();
+        let v = 172.74 + 0.00111588 * d;
+        let m = 357.529 + 0.9856003 * d;
+        let n = 20.020 + 0.0830853 * d + 0.329 * sin(v);
+        let j = 66.115 + 0.9025179 * d - 0.329 * sin(v);
+        let a = 1.915 * sin(m) + 0.020 * sin(2.0 * m);
+        let b = 5.555 * sin(n) + 0.168 * sin(2.0 * n);
+        let k = j + a - b;
+        let big_r = 1.00014 - 0.01671 * cos(m) - 0.00014 * cos(2.0 * m);
+        let r = 5.20872 - 0.25208 * cos(n) - 0.00611 * cos(2.0 * n);
+        let delta = (r * r + big_r * big_r - 2.0 * r * big_r * cos(k)).sqrt();
+        let psi = (big_r / delta * sin(k)).asin().to_degrees();
+
+        // Light time
+        let t = d - delta / 173.0;
+        let u1 = 163.8069 + 203.4058646 * t + psi - b;
+        let u2 = 358.4140 + 101.2916335 * t + psi - b;
+        let u3 = 5.7176 + 50.2345180 * t + psi - b;
+        let u4 = 224.8092 + 21.4879800 * t + psi - b;
+        let g = 331.18 + 50.310482 * t;
+        let h = 87.45 + 21.569231 * t;
+        match self {
+            PlanetMoon::Io => (
+                u1 + 0.473 * sin(2.0 * (u1 - u2)),
+                5.9057 - 0.0244 * cos(2.0 * (u1 - u2)),
+            ),
+            PlanetMoon::Europa => (
+                u2 + 1.065 * sin(2.0 * (u2 - u3)),
+                9.3966 - 0.0882 * cos(2.0 * (u2 - u3)),
+            ),
+            PlanetMoon::Ganymede => (u3 + 0.165 * sin(g), 14.9883 - 0.0216 * cos(g)),
+            _ => (u4 + 0.843 * sin(h), 26.3627 - 0.1939 * cos(h)),
+        }
+    }
+
+    /// Position of a moon of Saturn in the plane of Saturn's equator
+    ///
+    /// Gives the angle from the node of the equator on the B1950 ecliptic, and the distance in Saturn radii.
+    /// The mean longitudes, the largest periodic terms, and the equations of center are from Meeus,
+    /// Astronomical Algorithms, Ch. 46. The small inclinations of the orbits to the equator are left out.
+    fn saturnian(self, jde: f64) -> (f64, f64) {
+        let t1 = jde - 2411093.0;
+        let t2 = t1 / 365.25;
+        let t3 = (jde - 2433282.423) / 365.25 + 1950.0;
+        let t4 = jde - 2411368.0;
+        let t5 = t4 / 365.25;
+        let w0 = 5.095 * (t3 - 1866.39);
+        let w1 = 74.4 + 32.39 * t2;
+        let w2 = 134.3 + 92.62 * t2;
+        let w3 = 42.0 - 0.5118 * t5;
+        let w4 = 276.59 + 0.5118 * t5;
+
+        let (lambda, r) = match self {
+            PlanetMoon::Mimas => {
+                let l = 127.64 + 381.994497 * t1
+                    - 43.57 * sin(w0)
+                    - 0.720 * sin(3.0 * w0)
+                    - 0.02144 * sin(5.0 * w0);
+                let m = l - (106.1 + 365.549 * t2);
+                let c = 2.18287 * sin(m) + 0.025988 * sin(2.0 * m) + 0.00043 * sin(3.0 * m);
+                (l + c, 3.06879 / (1.0 + 0.01905 * cos(m + c)))
+            }
+            PlanetMoon::Enceladus => {
+                let l = 200.317 + 262.7319002 * t1 + 0.25667 * sin(w1) + 0.20883 * sin(w2);
+                let m = l - (309.107 + 123.44121 * t2);
+                let c = 0.55577 * sin(m) + 0.00168 * sin(2.0 * m);
+                (l + c, 3.94118 / (1.0 + 0.00485 * cos(m + c)))
+            }
+            PlanetMoon::Tethys => (
+                285.306
+                    + 190.69791226 * t1
+                    + 2.063 * sin(w0)
+                    + 0.03409 * sin(3.0 * w0)
+                    + 0.001015 * sin(5.0 * w0),
+                4.880998,
+            ),
+            PlanetMoon::Dione => {
+                let l = 254.712 + 131.53493193 * t1 - 0.0215 * sin(w1) - 0.01733 * sin(w2);
+                let m = l - (174.8 + 30.820 * t2);
+                let c = 0.24717 * sin(m) + 0.00033 * sin(2.0 * m);
+                (l + c, 6.24871 / (1.0 + 0.002157 * cos(m + c)))
+            }
+            PlanetMoon::Rhea => (
+                359.4727 + 79.69004720 * t1 + 0.086754 * sin(345.0 - 10.057 * t2),
+                8.725924,
+            ),
+            _ => {
+                // Titan, with its periapsis moving at the rate of W4
+                const E: f64 = 0.0289;
+                let l = 261.1582 + 22.57697855 * t4 + 0.074025 * sin(w3);
+                let m = l - w4;
+                let c = (2.0 * E * sin(m) + 1.25 * E * E * sin(2.0 * m)).to_degrees();
+                (l + c, 20.216193 * (1.0 - E * E) / (1.0 + E * cos(m + c)))
+            }
+        };
+        (lambda - 168.8112, r)
+    }
+
+    /// Position of the planet relative to the earth, in AU, J2000 equatorial
+    fn planetcart(self, d: time::Date) -> Vector {
+        let p = self.planet().locationcart(d);
+        let e = sol::EARTH.locationcart(d);
+        sub(p, e)
+    }
+
+    /// Position of the moon relative to its planet in planet radii, J2000 equatorial
+    fn offsetcart(self, d: time::Date) -> Vector {
+        let pole = towards(self.parent().pole.0, self.parent().pole.1);
+        let (x, angle, r) = match self.is_jovian() {
+            true => {
+                let to_earth = scale(self.planetcart(d), -1.0);
+                let (u, r) = self.jovian(d);
+                (unit(sub(to_earth, scale(pole, dot(to_earth, pole)))), u, r)
+            }
+            false => {
+                let light = self.planet().distance(d) * LIGHT_DAY_PER_AU;
+                let (u, r) = self.saturnian(d.julian() - light);
+                let node = towards(SATURN_NODE.0, SATURN_NODE.1);
+                (unit(sub(node, scale(pole, dot(node, pole)))), u, r)
+            }
+        };
+        // Moons orbit in the direction the planet rotates
+        let y = cross(pole, x);
+        (
+            r * (cos(angle) * x.0 + sin(angle) * y.0),
+            r * (cos(angle) * x.1 + sin(angle) * y.1),
+            r * (cos(angle) * x.2 + sin(angle) * y.2),
+        )
+    }
+
+    /// Position relative to the earth in AU, J2000 equatorial
+    fn geocart(self, d: time::Date) -> Vector {
+        let s = scale(self.offsetcart(d), self.parent().radius / KM_PER_AU);
+        let p = self.planetcart(d);
+        (p.0 + s.0, p.1 + s.1, p.2 + s.2)
+    }
+
+    /// Heliocentric position in AU, J2000 equatorial, like [`sol::Planet::locationcart`]
+    pub fn locationcart(self, d: time::Date) -> (f64, f64, f64) {
+        let s = scale(self.offsetcart(d), self.parent().radius / KM_PER_AU);
+        let p = self.planet().locationcart(d);
+        (p.0 + s.0, p.1 + s.1, p.2 + s.2)
+    }
+
+    /// Geocentric equatorial coordinates, in the same frame as [`sol::Planet::location`]
+    pub fn location(self, d: time::Date) -> coord::Coord {
+        let (x, y, z) = self.geocart(d);
+        coord::Coord::from_cartesian(x, y, z)
+    }
+
+    /// Distance from the earth in AU
+    pub fn distance(self, d: time::Date) -> f64 {
+        dot(self.geocart(d), self.geocart(d)).sqrt()
+    }
+
+    pub fn angdia(self, d: time::Date) -> time::Angle {
+        time::Angle::from_radians(2.0 * self.physical().0 / KM_PER_AU / self.distance(d))
+    }
+
+    /// Magnitude, from the distance alone, ignoring the phase of the moon
+    pub fn magnitude(self, d: time::Date) -> f64 {
+        let (x, y, z) = self.planet().locationcart(d);
+        self.brightness((x * x + y * y + z * z).sqrt(), self.distance(d))
+    }
+
+    /// Magnitude at `r` AU from the sun and `delta` AU from the observer
+    pub fn brightness(self, r: f64, delta: f64) -> f64 {
+        self.physical().1 + 5.0 * (r * delta).log10()
+    }
+
+    /// Offset from the center of the planet in arcseconds, east and north
+    ///
+    /// These are along the celestial axes, not the planet's equator.
+    pub fn offset(self, d: time::Date) -> (f64, f64) {
+        let (p, m) = (self.planetcart(d), self.geocart(d));
+        let (ra, de) = coord::Coord::from_cartesian(p.0, p.1, p.2).equatorial();
+        let (ra, de) = (ra.radians(), de.radians());
+        let east = (-ra.sin(), ra.cos(), 0.0);
+        let north = (-de.sin() * ra.cos(), -de.sin() * ra.sin(), de.cos());
+        let m = scale(m, 1.0 / dot(m, m).sqrt());
+        (
+            dot(m, east).to_degrees() * 3600.0,
+            dot(m, north).to_degrees() * 3600.0,
+        )
+    }
+
+    /// How far inside the disk of the planet the moon or its shadow is, positive during the phenomenon
+    ///
+    /// For transits and occultations this is the disk as seen from the earth, and for shadows and
+    /// eclipses, as seen from the sun. It's 1 at the center of the disk, and 0 at the limb.
+    pub fn phenomenon(self, d: time::Date, ph: Phenomenon) -> f64 {
+        let s = self.offsetcart(d);
+        let view = unit(match ph {
+            Phenomenon::Transit | Phenomenon::Occultation => self.planetcart(d),
+            Phenomenon::ShadowTransit | Phenomenon::Eclipse => self.planet().locationcart(d),
+        });
+        let behind = dot(s, view) > 0.0;
+        if behind != matches!(ph, Phenomenon::Occultation | Phenomenon::Eclipse) {
+            return -1.0;
+        }
+
+        // The disk of the planet is an ellipse, narrowest along the projection of its pole
+        let pole = towards(self.parent().pole.0, self.parent().pole.1);
+        let perp = sub(s, scale(view, dot(s, view)));
+        let pole_sky = sub(pole, scale(view, dot(pole, view)));
+        let tilt = dot(pole_sky, pole_sky);
+        let minor = ((1.0 - self.parent().flattening).powi(2) * tilt + (1.0 - tilt)).sqrt();
+        let along = dot(perp, unit(pole_sky));
+        let across = dot(perp, perp) - along * along;
+        1.0 - (across + (along / minor).powi(2)).sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Offset from the planet in its radii, west along its equator and north along its axis
+    /// as they're seen from the earth, as Meeus gives them
+    fn xy(m: PlanetMoon, d: time::Date) -> (f64, f64) {
+        let (s, view) = (m.offsetcart(d), unit(m.planetcart(d)));
+        let pole = towards(m.parent().pole.0, m.parent().pole.1);
+        let north = unit(sub(pole, scale(view, dot(pole, view))));
+        let east = cross(north, view);
+        (-dot(s, east), dot(s, north))
+    }
+
+    #[test]
+    fn meeus_galilean_moons() {
+        // Example 44.a, 1992 December 16 at 0h UT
+        let d = time::Date::from_calendar(1992, 12, 16, time::Angle::from_turns(0.0));
+        for (m, expected) in [
+            (PlanetMoon::Io, (-3.44, 0.21)),
+            (PlanetMoon::Europa, (7.44, 0.25)),
+            (PlanetMoon::Ganymede, (1.24, 0.65)),
+            (PlanetMoon::Callisto, (7.08, 1.10)),
+        ] {
+            let (x, y) = xy(m, d);
+            assert!(
+                (x - expected.0).abs() < 0.05 && (y - expected.1).abs() < 0.05,
+                "{m:?} {x} {y}"
+            );
+        }
+    }
+}
@@ -0,0 +1,355 @@
+use crate::catalog::Catalog;
+use crate::error::Error;
+use crate::query::{self, property_of, Property};
+use crate::value::{CelObj, RefFrame, Value};
+use crate::{event, lunar};
+use pracstro::{coord, time};
+use std::fmt;
+use std::sync::Arc;
+
+const KM_PER_AU: f64 = 149_597_870.7;
+const EARTH_RADIUS: f64 = 6378.14; // Kilometers
+const MOON_RADIUS: f64 = 1737.4;
+
+/// How far occultations are searched for at a time, in days. About a lunation, so the moon covers
+/// every right ascension once
+const MONTH: f64 = 30.0;
+
+/// Stars that can be occulted, with their names
+pub type Stars = Arc<[(String, CelObj)]>;
+
+/// The stars in a catalog, in order of name
+pub fn stars(cat: &Catalog) -> Stars {
+    let mut stars: Vec<(String, CelObj)> = cat
+        .iter()
+        .filter(|(_, o)| matches!(o, CelObj::Star(_)))
+        .map(|(n, o)| (n.clone(), o.clone()))
+        .collect();
+    stars.sort_by(|a, b| a.0.cmp(&b.0));
+    stars.into()
+}
+
+/// Which half of the occulting body's limb a star goes behind or comes out from
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Limb {
+    Bright,
+    Dark,
+}
+impl fmt::Display for Limb {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Limb::Bright => "Bright",
+                Limb::Dark => "Dark",
+            }
+        )
+    }
+}
+
+/// Details of an occultation that can be given as properties
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Circumstance {
+    /// Name of the star occulted
+    Star,
+    /// Geocentric conjunction in right ascension
+    Conjunction,
+    /// How far north of the occulting body's center the star is at conjunction
+    Separation,
+    Disappearance,
+    DisappearanceAngle,
+    DisappearanceLimb,
+    Reappearance,
+    ReappearanceAngle,
+    ReappearanceLimb,
+}
+
+/// A star going behind or coming out from the limb
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Contact {
+    pub date: time::Date,
+    /// Position angle of the star from the center of the occulting body, from the north through the east
+    pub angle: time::Angle,
+    pub limb: Limb,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Occultation {
+    pub star: String,
+    pub conjunction: time::Date,
+    /// Declination of the star less that of the occulting body at conjunction, in degrees
+    pub separation: f64,
+    /// Seen from the observer, if there is one. Either can be missing if the star is already
+    /// hidden, or still hidden, at the edge of the time searched
+    pub disappearance: Option<Contact>,
+    pub reappearance: Option<Contact>,
+}
+
+/// Position of the occulting body on the equinox of the date, its distance in AU, and its radius
+/// in degrees, as seen from the observer if there is one
+fn occulter(obj: &CelObj, rf: &RefFrame) -> Result<(coord::Coord, f64, f64), Error> {
+    match obj {
+        // pracstro's moon is off by too much to tell if a star is behind it
+        CelObj::Moon => {
+            let (c, dist) = lunar::topocentric(rf);
+            let radius = (MOON_RADIUS / dist).asin().to_degrees();
+            Ok((c, dist / KM_PER_AU, radius))
+        }
+        _ => {
+            let Value::Ang(angdia, _) = property_of(obj, Property::AngDia, rf)? else {
+                unreachable!();
+            };
+            let Value::Dist(dist) = property_of(obj, Property::Distance, rf)? else {
+                unreachable!();
+            };
+            Ok((query::of_date(obj, rf)?, dist, angdia.degrees() / 2.0))
+        }
+    }
+}
+
+/// Position angle of `b` seen from `a`, from the north through the east
+fn position_angle(a: coord::Coord, b: coord::Coord) -> time::Angle {
+    let ((ra, de), (rb, db)) = (a.equatorial(), b.equatorial());
+    time::Angle::atan2(
+        db.cos() * (rb - ra).sin(),
+        db.sin() * de.cos() - db.cos() * de.sin() * (rb - ra).cos(),
+    )
+}
+
+fn contact(obj: &CelObj, star: &CelObj, rf: &RefFrame) -> Result<Contact, Error> {
+    let (o, _, _) = occulter(obj, rf)?;
+    let angle = position_angle(o, query::of_date(star, rf)?);
+    // The bright limb is the half facing the sun
+    let sun = position_angle(o, lunar::sun_position(rf.date));
+    Ok(Contact {
+        date: rf.date,
+        angle,
+        limb: if (angle - sun).to_latitude().degrees().abs() < 90.0 {
+            Limb::Bright
+        } else {
+            Limb::Dark
+        },
+    })
+}
+
+/// Disappearance and reappearance of a star seen from the observer, around a geocentric conjunction
+fn local(
+    obj: &CelObj,
+    star: &CelObj,
+    conjunction: time::Date,
+    reach: f64,
+    rf: &RefFrame,
+) -> Result<(Option<Contact>, Option<Contact>), Error> {
+    let at = |d: time::Date| RefFrame { date: d, ..*rf };
+    let shift = |days: f64| time::Date::from_julian(conjunction.julian() + days);
+    let behind = |d| {
+        let (o, _, r) = occulter(obj, &at(d))?;
+        Ok(o.dist(query::of_date(star, &at(d))?).degrees() - r)
+    };
+    // Long enough for the body to cross the whole region its parallax can put it in
+    let geo = RefFrame {
+        latlong: None,
+        ..*rf
+    };
+    let geocentric = |d| occulter(obj, &RefFrame { date: d, ..geo }).map(|x| x.0);
+    let rate = geocentric(shift(-0.01))?
+        .dist(geocentric(shift(0.01))?)
+        .degrees()
+        / 0.02;
+    let half = (2.0 * reach / rate).min(5.0);
+    let found = event::crossings(behind, shift(-half), shift(half), half / 200.0)?;
+    let hides = found.iter().find(|c| !c.rising);
+    let shows = found
+        .iter()
+        .find(|c| c.rising && hides.is_none_or(|h| h.date.julian() < c.date.julian()));
+    Ok((
+        hides.map(|c| contact(obj, star, &at(c.date))).transpose()?,
+        shows.map(|c| contact(obj, star, &at(c.date))).transpose()?,
+    ))
+}
+
+/// Every occultation of the stars by the moon or a planet with a geocentric conjunction between
+/// the date and `end`
+///
+/// Without an observer, these are the occultations seen from somewhere on the earth. With one,
+/// they're the ones seen from there, whether or not the body is above the horizon.
+pub fn search(
+    obj: &CelObj,
+    stars: &[(String, CelObj)],
+    rf: &RefFrame,
+    end: time::Date,
+) -> Result<Vec<Occultation>, Error> {
+    if !matches!(obj, CelObj::Moon | CelObj::Planet(_)) || rf.from.is_some() {
+        return Err(Error::Unsupported(
+            "Occultations".to_string(),
+            "anything but the moon or a planet",
+        ));
+    }
+    let geo = |d: time::Date| RefFrame {
+        date: d,
+        latlong: None,
+        ..*rf
+    };
+    // The moon moves 15° a day at most, so conjunctions can be told apart with daily positions
+    let days: Vec<time::Date> = (0..)
+        .map(|i| time::Date::from_julian(rf.date.julian() + i as f64))
+        .take_while(|d| d.julian() < end.julian())
+        .chain([end])
+        .collect();
+    let track = days
+        .iter()
+        .map(|d| Ok(occulter(obj, &geo(*d))?.0.equatorial().0))
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let mut found = vec![];
+    for (name, star) in stars {
+        let (ra, _) = query::of_date(star, &geo(rf.date))?.equatorial();
+        let near = |i: usize| (track[i] - ra).to_latitude().degrees();
+        for i in 0..days.len() - 1 {
+            // Crossing the star's right ascension, rather than the opposite one
+            if (near(i) < 0.0) == (near(i + 1) < 0.0) || (near(i) - near(i + 1)).abs() > 90.0 {
+                continue;
+            }
+            let ahead = |d| {
+                let (o, _, _) = occulter(obj, &geo(d))?;
+                let s = query::of_date(star, &geo(d))?;
+                Ok((o.equatorial().0 - s.equatorial().0).sin())
+            };
+            let span = days[i + 1].julian() - days[i].julian();
+            for c in event::crossings(ahead, days[i], days[i + 1], span)? {
+                let (o, dist, radius) = occulter(obj, &geo(c.date))?;
+                let s = query::of_date(star, &geo(c.date))?;
+                let separation = (s.equatorial().1 - o.equatorial().1)
+                    .to_latitude()
+                    .degrees();
+                // The parallax of the body, from anywhere on the earth
+                let reach = radius + (EARTH_RADIUS / (dist * KM_PER_AU)).asin().to_degrees();
+                if separation.abs() > reach {
+                    continue;
+                }
+                let (disappearance, reappearance) = match rf.latlong {
+                    Some(_) => local(obj, star, c.date, reach, rf)?,
+                    None => (None, None),
+                };
+                if rf.latlong.is_some() && disappearance.is_none() && reappearance.is_none() {
+                    continue;
+                }
+                found.push(Occultation {
+                    star: name.clone(),
+                    conjunction: c.date,
+                    separation,
+                    disappearance,
+                    reappearance,
+                });
+            }
+        }
+    }
+    found.sort_by(|a, b| a.conjunction.julian().total_cmp(&b.conjunction.julian()));
+    Ok(found)
+}
+
+/// The first occultation with a geocentric conjunction less than `window` days after the date
+///
+/// Searches a month at a time, so nearby occultations are found without searching the whole window.
+pub fn next(
+    obj: &CelObj,
+    stars: &[(String, CelObj)],
+    rf: &RefFrame,
+    window: f64,
+) -> Result<Option<Occultation>, Error> {
+    let end = rf.date.julian() + window;
+    let mut start = rf.date.julian();
+    while start < end {
+        let stop = (start + MONTH).min(end);
+        let at = RefFrame {
+            date: time::Date::from_julian(start),
+            ..*rf
+        };
+        let found = search(obj, stars, &at, time::Date::from_julian(stop))?;
+        if let Some(o) = found.into_iter().next() {
+            return Ok(Some(o));
+        }
+        start = stop;
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog;
+    use crate::value::Refraction;
+
+    fn date(y: i64, m: u8, d: u8) -> time::Date {
+        time::Date::from_calendar(y, m, d, time::Angle::from_turns(0.0))
+    }
+
+    fn aldebaran(rf: &RefFrame, end: time::Date) -> Vec<Occultation> {
+        let stars = stars(&catalog::read());
+        search(&CelObj::Moon, &stars, rf, end)
+            .unwrap()
+            .into_iter()
+            .filter(|o| o.star == "aldebaran")
+            .collect()
+    }
+
+    #[test]
+    fn aldebaran_series() {
+        // The moon passed in front of Aldebaran every month from 2015 to 2018
+        let rf = RefFrame {
+            latlong: None,
+            date: date(2016, 9, 1),
+            refraction: Refraction::Standard,
+            from: None,
+        };
+        let found = aldebaran(&rf, date(2017, 4, 1));
+        let days: Vec<(i64, u8, u8)> = found
+            .iter()
+            .map(|o| {
+                let (y, m, d, _) = o.conjunction.calendar();
+                (y, m, d)
+            })
+            .collect();
+        assert_eq!(
+            days,
+            [
+                (2016, 9, 21),
+                (2016, 10, 19),
+                (2016, 11, 15),
+                (2016, 12, 13),
+                (2017, 1, 9),
+                (2017, 2, 5),
+                (2017, 3, 5)
+            ]
+        );
+        assert!(found.iter().all(|o| o.separation.abs() < 1.0));
+    }
+
+    #[test]
+    fn aldebaran_from_chicago() {
+        // Seen in the evenings of 2016 December 12 and 2017 March 4 across North America, with the
+        // moon waxing, so Aldebaran went behind the dark limb and came out from the bright one
+        let deg = time::Angle::from_degrees;
+        let rf = RefFrame {
+            latlong: Some((deg(41.88), deg(-87.63), 0.0)),
+            date: date(2016, 12, 1),
+            refraction: Refraction::Standard,
+            from: None,
+        };
+        let found = aldebaran(&rf, date(2017, 4, 1));
+        assert_eq!(found.len(), 2);
+        for o in found {
+            let (hide, show) = (o.disappearance.unwrap(), o.reappearance.unwrap());
+            assert_eq!((hide.limb, show.limb), (Limb::Dark, Limb::Bright));
+            let hours = (show.date.julian() - hide.date.julian()) * 24.0;
+            assert!(hours > 0.0 && hours < 1.5);
+            let (d, c, s) = (
+                hide.date.julian(),
+                o.conjunction.julian(),
+                show.date.julian(),
+            );
+            assert!((c - d).abs() < 0.1 && (s - c).abs() < 0.1);
+        }
+    }
+}
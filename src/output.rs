@@ -12,6 +12,10 @@ pub struct Driver {
     pub query: fn(Vec<Value>) -> (),
     /// The formatting in a ephemeris query
     pub ephemq: fn(Vec<Value>, &[Property], time::Date) -> (),
+    /// Headers for columns, in a table of objects rather than dates
+    pub nameheader: fn(&[Property]) -> (),
+    /// The formatting of a row in a table of objects
+    pub namedq: fn(Vec<Value>, &[Property], &str) -> (),
     /// Ending information
     pub footer: fn() -> (),
 }
@@ -19,12 +23,18 @@ pub struct Driver {
 pub fn nop() {}
 pub fn nop_fa(_: &[Property]) {}
 
-fn term_proph(rs: &[Property]) {
+fn term_header(first: &str, rs: &[Property]) {
     println!("{:=<1$}", "", 29 * rs.len() + 22);
-    print!("{:^22}", "Date");
+    print!("{:^22}", first);
     rs.iter().for_each(|x| print!("{:^29}", x.to_string()));
     println!("\n{:=<1$}", "", 29 * rs.len() + 22);
 }
+fn term_proph(rs: &[Property]) {
+    term_header("Date", rs);
+}
+fn term_nameh(rs: &[Property]) {
+    term_header("Object", rs);
+}
 fn term_q(rs: Vec<Value>) {
     println!(
         "{}",
@@ -39,23 +49,36 @@ fn term_eq(rs: Vec<Value>, _: &[Property], d: time::Date) {
     rs.iter().for_each(|x| print!("{:<29}", x.to_string()));
     println!();
 }
+fn term_nq(rs: Vec<Value>, _: &[Property], name: &str) {
+    print!("{:<22}", name);
+    rs.iter().for_each(|x| print!("{:<29}", x.to_string()));
+    println!();
+}
 pub const TERM: Driver = Driver {
     start: nop,
     propheader: term_proph,
     query: term_q,
     ephemq: term_eq,
+    nameheader: term_nameh,
+    namedq: term_nq,
     footer: nop,
 };
 
-fn csv_proph(rs: &[Property]) {
+fn csv_header(first: &str, rs: &[Property]) {
     println!(
-        "Date,{}",
+        "{first},{}",
         rs.iter()
             .map(|x| x.to_string())
             .collect::<Vec<String>>()
             .join(",")
     )
 }
+fn csv_proph(rs: &[Property]) {
+    csv_header("Date", rs);
+}
+fn csv_nameh(rs: &[Property]) {
+    csv_header("Object", rs);
+}
 fn csv_q(rs: Vec<Value>) {
     println!(
         "{}",
@@ -75,11 +98,22 @@ fn csv_eq(rs: Vec<Value>, _: &[Property], d: time::Date) {
             .join(",")
     )
 }
+fn csv_nq(rs: Vec<Value>, _: &[Property], name: &str) {
+    println!(
+        "{name},{}",
+        rs.iter()
+            .map(|x| x.to_string())
+            .collect::<Vec<String>>()
+            .join(",")
+    )
+}
 pub const CSV: Driver = Driver {
     start: nop,
     propheader: csv_proph,
     query: csv_q,
     ephemq: csv_eq,
+    nameheader: csv_nameh,
+    namedq: csv_nq,
     footer: nop,
 };
 
@@ -98,6 +132,13 @@ fn json_eq(rs: Vec<Value>, nm: &[Property], d: time::Date) {
         .for_each(|(n, x)| print!("\"{}\": {:#},", nm[n], x));
     print!("\"isq\": true }},");
 }
+fn json_nq(rs: Vec<Value>, nm: &[Property], name: &str) {
+    print!("{{ \"object\": \"{name}\",");
+    rs.iter()
+        .enumerate()
+        .for_each(|(n, x)| print!("\"{}\": {:#},", nm[n], x));
+    print!("\"isq\": true }},");
+}
 fn json_footer() {
     print!("{{\"isq\": false}} ] }}");
 }
@@ -106,5 +147,7 @@ pub const JSON: Driver = Driver {
     propheader: nop_fa,
     query: json_q,
     ephemq: json_eq,
+    nameheader: nop_fa,
+    namedq: json_nq,
     footer: json_footer,
 };
@@ -1,12 +1,21 @@
-use crate::{value, timestep, query::Property};
+use crate::{
+    catalog,
+    eclipse::Circumstance,
+    error::Error,
+    event::{Aspect, Terms},
+    galactic, moons,
+    occultation::{self, Circumstance as Occultation},
+    query::{Airmass, Center, Plane, Property, Twilight},
+    timestep, value,
+};
 use chrono::prelude::*;
-use pracstro::{coord, time};
+use pracstro::{coord, sol, time};
 
 fn suffix_num(s: &str, j: &str) -> Option<f64> {
     s.strip_suffix(j)?.parse::<f64>().ok()
 }
 
-pub fn angle(s: &str) -> Result<time::Angle, &'static str> {
+pub fn angle(s: &str) -> Result<time::Angle, Error> {
     let sl = &s.to_lowercase(); // This can usually be guaranteed, except in argument parsing
     if let Some(n) = suffix_num(sl, "e") {
         Ok(time::Angle::from_degrees(n))
@@ -25,12 +34,12 @@ pub fn angle(s: &str) -> Result<time::Angle, &'static str> {
     } else if let Some(n) = suffix_num(sl, "rad") {
         Ok(time::Angle::from_radians(n))
     } else {
-        Err("Invalid Angle")
+        Err(Error::BadAngle("Unknown unit"))
     }
 }
 
 /// A step in time, returns (years, months, days, hours, minutes, seconds)
-pub fn step(sm: &str) -> Result<timestep::Step, &'static str> {
+pub fn step(sm: &str) -> Result<timestep::Step, Error> {
     let s = &sm.to_lowercase(); // This can usually be guaranteed, except in argument parsing
     if let Some(n) = suffix_num(s, "y") {
         Ok(timestep::Step::M(chrono::Months::new(n as u32 * 12)))
@@ -47,32 +56,32 @@ pub fn step(sm: &str) -> Result<timestep::Step, &'static str> {
     } else if let Some(n) = suffix_num(s, "s") {
         Ok(timestep::Step::S(n))
     } else {
-        Err("Bad interval")
+        Err(Error::BadInterval("Unknown unit"))
     }
 }
 
 /// The inbuilt RFC3339/ISO6901 date parser in chrono does not support subsets of the formatting.
-pub fn date(sm: &str) -> Result<time::Date, &'static str> {
+pub fn date(sm: &str) -> Result<time::Date, Error> {
     let s = &sm.to_lowercase(); // This can usually be guaranteed, except in argument parsing
     if s == "now" {
         Ok(time::Date::now())
     } else if s.starts_with("-") {
         Ok(timestep::step_back_date(
             time::Date::now(),
-            step(s.strip_prefix("-").ok_or("Bad prefix")?)?,
+            step(s.strip_prefix("-").ok_or(Error::BadDate("Bad prefix"))?)?,
         ))
     } else if s.starts_with("+") {
         Ok(timestep::step_forward_date(
             time::Date::now(),
-            step(s.strip_prefix("+").ok_or("Bad prefix")?)?,
+            step(s.strip_prefix("+").ok_or(Error::BadDate("Bad prefix"))?)?,
         ))
     } else if s.starts_with("@") {
         Ok(time::Date::from_unix(
             s.strip_prefix("@")
-                .ok_or("")?
+                .ok_or(Error::BadDate("Bad prefix"))?
                 .parse()
                 .ok()
-                .ok_or("Bad Number")?,
+                .ok_or(Error::BadDate("Bad Number"))?,
         ))
     } else if let Some(n) = suffix_num(s, "u") {
         Ok(time::Date::from_unix(n))
@@ -91,30 +100,30 @@ pub fn date(sm: &str) -> Result<time::Date, &'static str> {
             NaiveDateTime::from(d).and_utc().timestamp() as f64,
         ))
     } else {
-        Err("Invalid Date")
+        Err(Error::BadDate("Unknown format"))
     }
 }
 
-pub fn ephemq(s: &str) -> Result<(time::Date, timestep::Step, time::Date), &'static str> {
+pub fn ephemq(s: &str) -> Result<(time::Date, timestep::Step, time::Date), Error> {
     let mut eq = s.split(',');
-    let start = eq.next().ok_or("Bad CSV")?;
-    let ste = eq.next().ok_or("Bad CSV")?;
-    let end = eq.next().ok_or("Bad CSV")?;
+    let start = eq.next().ok_or(Error::BadDate("Bad CSV"))?;
+    let ste = eq.next().ok_or(Error::BadInterval("Bad CSV"))?;
+    let end = eq.next().ok_or(Error::BadDate("Bad CSV"))?;
     Ok((date(start)?, step(ste)?, date(end)?))
 }
 
-pub fn latlong(s: &str) -> Result<value::Location, &'static str> {
-    fn long(s: &str) -> Result<time::Angle, &'static str> {
+pub fn latlong(s: &str) -> Result<value::Location, Error> {
+    fn long(s: &str) -> Result<time::Angle, Error> {
         if let Ok(n) = s.parse::<f64>() {
             Ok(time::Angle::from_degrees(n))
         } else {
             angle(s)
         }
     }
-    fn lat(s: &str) -> Result<time::Angle, &'static str> {
+    fn lat(s: &str) -> Result<time::Angle, Error> {
         let unchecked_l = long(s)?;
         if unchecked_l.to_latitude().degrees() > 90.0 {
-            Err("Latitude over 90 degrees")
+            Err(Error::BadLocation("Latitude over 90 degrees"))
         } else {
             Ok(unchecked_l)
         }
@@ -123,32 +132,105 @@ pub fn latlong(s: &str) -> Result<value::Location, &'static str> {
         return Ok(None);
     };
     let mut eq = s.split(',');
-    let lats = eq.next().ok_or("Bad CSV")?;
-    let longs = eq.next().ok_or("Bad CSV")?;
-    Ok(Some((lat(lats)?, long(longs)?)))
+    let lats = eq.next().ok_or(Error::BadLocation("Bad CSV"))?;
+    let longs = eq.next().ok_or(Error::BadLocation("Bad CSV"))?;
+    let elev = match eq.next() {
+        Some(e) => e
+            .strip_suffix("m")
+            .unwrap_or(e)
+            .parse::<f64>()
+            .ok()
+            .ok_or(Error::BadLocation("Bad Elevation"))?,
+        None => 0.0,
+    };
+    Ok(Some((lat(lats)?, long(longs)?, elev)))
+}
+
+/// `none`, `standard`, or a temperature and pressure as `°C,hPa`
+pub fn refraction(sm: &str) -> Result<value::Refraction, Error> {
+    let s = &sm.to_lowercase(); // This can usually be guaranteed, except in argument parsing
+    match s.as_str() {
+        "none" => Ok(value::Refraction::None),
+        "standard" => Ok(value::Refraction::Standard),
+        _ => {
+            let mut eq = s.split(',');
+            let mut num = || {
+                eq.next()
+                    .and_then(|x| x.parse::<f64>().ok())
+                    .ok_or(Error::BadRefraction("Expected none, standard, or °C,hPa"))
+            };
+            Ok(value::Refraction::Atmosphere(num()?, num()?))
+        }
+    }
+}
+
+/// The planet the sky is seen from, which is `None` for the earth
+pub fn observer(s: &str) -> Result<Option<&'static sol::Planet>, Error> {
+    match sol::PLANETS
+        .into_iter()
+        .find(|p| p.name.eq_ignore_ascii_case(s))
+    {
+        Some(p) if *p == sol::EARTH => Ok(None),
+        Some(p) => Ok(Some(p)),
+        None => Err(Error::UnknownObject(s.to_string())),
+    }
 }
 
-pub fn object(
-    sm: &str,
-    cat: &std::collections::HashMap<&'static str, value::CelObj>,
-) -> Result<value::CelObj, &'static str> {
+pub fn object(sm: &str, cat: &catalog::Catalog) -> Result<value::CelObj, Error> {
     let s = sm.to_lowercase();
     if s.starts_with("latlong:") {
-        let ll = latlong(s.strip_prefix("latlong:").ok_or("Bad prefix")?)?
-            .ok_or("Raw coordinate must not be none")?;
-        return Ok(value::CelObj::Crd(coord::Coord::from_equatorial(ll.1, ll.0)));
+        let ll = latlong(
+            s.strip_prefix("latlong:")
+                .ok_or(Error::BadLocation("Bad prefix"))?,
+        )?
+        .ok_or(Error::BadLocation("Raw coordinate must not be none"))?;
+        return Ok(value::CelObj::Crd(coord::Coord::from_equatorial(
+            ll.1, ll.0,
+        )));
     };
-    cat.get(s.as_str()).cloned().ok_or("Unknown Object")
+    if let Some(lb) = s.strip_prefix("galactic:") {
+        // Longitude comes first, the other way around from a latitude and longitude
+        let (l, b) = lb.split_once(',').ok_or(Error::BadLocation("Bad CSV"))?;
+        let (b, l, _) = latlong(&format!("{b},{l}"))?
+            .ok_or(Error::BadLocation("Raw coordinate must not be none"))?;
+        return Ok(value::CelObj::Crd(galactic::from_galactic(l, b)));
+    };
+    cat.get(s.as_str()).cloned().ok_or(Error::UnknownObject(s))
 }
 
-pub fn property(
-    sm: &str,
-    cat: &std::collections::HashMap<&'static str, value::CelObj>,
-) -> Result<Property, &'static str> {
+/// Rectangular coordinates, like `helioxyz`, `geoeclxyz`, or `xyz:km`
+fn rectangular(s: &str) -> Option<Property> {
+    let (s, unit) = match s.split_once(':') {
+        Some((s, "au")) => (s, value::DistUnit::Au),
+        Some((s, "km")) => (s, value::DistUnit::Km),
+        Some(_) => return None,
+        None => (s, value::DistUnit::Au),
+    };
+    let s = s.strip_suffix("xyz")?;
+    let (s, center) = match s.strip_prefix("helio") {
+        Some(s) => (s, Center::Sun),
+        None => (s.strip_prefix("geo").unwrap_or(s), Center::Earth),
+    };
+    let plane = match s {
+        "" | "equ" => Plane::Equatorial,
+        "ecl" => Plane::Ecliptic,
+        _ => return None,
+    };
+    Some(Property::Rectangular(center, plane, unit))
+}
+
+pub fn property(sm: &str, cat: &catalog::Catalog) -> Result<Property, Error> {
     let s = &sm.to_lowercase();
+    if let Some(p) = rectangular(s) {
+        return Ok(p);
+    }
+    if let Some(o) = s.strip_prefix("conjunction:") {
+        return Ok(Property::NextConjunction(object(o, cat)?));
+    }
     if s.starts_with("angbetween:") {
         return Ok(Property::AngBet(object(
-            s.strip_prefix("angbetween:").ok_or("Bad prefix")?,
+            s.strip_prefix("angbetween:")
+                .ok_or(Error::UnknownProperty(s.to_string()))?,
             cat,
         )?));
     };
@@ -156,6 +238,8 @@ pub fn property(
         "equ" | "equa" | "equatorial" => Ok(Property::Equatorial),
         "horiz" | "horizontal" => Ok(Property::Horizontal),
         "ecl" | "ecliptic" => Ok(Property::Ecliptic),
+        "gal" | "galactic" => Ok(Property::Galactic),
+        "sgal" | "supergalactic" => Ok(Property::Supergalactic),
         "dist" | "distance" => Ok(Property::Distance),
         "mag" | "magnitude" | "brightness" => Ok(Property::Magnitude),
         "phase" => Ok(Property::PhaseDefault),
@@ -163,8 +247,106 @@ pub fn property(
         "phasename" => Ok(Property::PhaseName),
         "angdia" => Ok(Property::AngDia),
         "phaseprecent" | "illumfrac" => Ok(Property::IllumFrac),
+        "phaseangle" => Ok(Property::PhaseAngle),
+        "aspect" => Ok(Property::Aspect),
+        "conjunction" => Ok(Property::NextAspect(Aspect::Conjunction)),
+        "inferiorconjunction" => Ok(Property::NextAspect(Aspect::InferiorConjunction)),
+        "superiorconjunction" => Ok(Property::NextAspect(Aspect::SuperiorConjunction)),
+        "opposition" => Ok(Property::NextAspect(Aspect::Opposition)),
+        "greatesteast" => Ok(Property::NextAspect(Aspect::GreatestEast)),
+        "greatestwest" => Ok(Property::NextAspect(Aspect::GreatestWest)),
+        "stationretrograde" => Ok(Property::NextAspect(Aspect::StationRetrograde)),
+        "stationdirect" => Ok(Property::NextAspect(Aspect::StationDirect)),
+        "occultedstar" => Ok(Property::Occultation(
+            Occultation::Star,
+            occultation::stars(cat),
+        )),
+        "nextoccultation" => Ok(Property::Occultation(
+            Occultation::Conjunction,
+            occultation::stars(cat),
+        )),
+        "occultsep" => Ok(Property::Occultation(
+            Occultation::Separation,
+            occultation::stars(cat),
+        )),
+        "disappearance" => Ok(Property::Occultation(
+            Occultation::Disappearance,
+            occultation::stars(cat),
+        )),
+        "disappearancepa" => Ok(Property::Occultation(
+            Occultation::DisappearanceAngle,
+            occultation::stars(cat),
+        )),
+        "disappearancelimb" => Ok(Property::Occultation(
+            Occultation::DisappearanceLimb,
+            occultation::stars(cat),
+        )),
+        "reappearance" => Ok(Property::Occultation(
+            Occultation::Reappearance,
+            occultation::stars(cat),
+        )),
+        "reappearancepa" => Ok(Property::Occultation(
+            Occultation::ReappearanceAngle,
+            occultation::stars(cat),
+        )),
+        "reappearancelimb" => Ok(Property::Occultation(
+            Occultation::ReappearanceLimb,
+            occultation::stars(cat),
+        )),
+        "season" => Ok(Property::Term(Terms::Seasons)),
+        "nextseason" => Ok(Property::NextTerm(Terms::Seasons)),
+        "crossquarter" => Ok(Property::Term(Terms::CrossQuarter)),
+        "nextcrossquarter" => Ok(Property::NextTerm(Terms::CrossQuarter)),
+        "solarterm" => Ok(Property::Term(Terms::Solar)),
+        "nextsolarterm" => Ok(Property::NextTerm(Terms::Solar)),
+        "eclipsetype" => Ok(Property::Eclipse(Circumstance::Kind)),
+        "eclipsemag" => Ok(Property::Eclipse(Circumstance::Magnitude)),
+        "gamma" => Ok(Property::Eclipse(Circumstance::Gamma)),
+        "eclipsebegin" => Ok(Property::Eclipse(Circumstance::Begin)),
+        "totalitybegin" => Ok(Property::Eclipse(Circumstance::TotalityBegin)),
+        "nexteclipse" | "eclipsemax" => Ok(Property::Eclipse(Circumstance::Maximum)),
+        "totalityend" => Ok(Property::Eclipse(Circumstance::TotalityEnd)),
+        "eclipseend" => Ok(Property::Eclipse(Circumstance::End)),
+        "obscuration" => Ok(Property::Eclipse(Circumstance::Obscuration)),
+        "eclipsebeginalt" => Ok(Property::Eclipse(Circumstance::BeginAltitude)),
+        "eclipsemaxalt" => Ok(Property::Eclipse(Circumstance::MaximumAltitude)),
+        "eclipseendalt" => Ok(Property::Eclipse(Circumstance::EndAltitude)),
+        "elong" | "elongation" => Ok(Property::Elongation),
+        "brightlimb" => Ok(Property::BrightLimb),
         "rise" => Ok(Property::Rise),
         "set" => Ok(Property::Set),
-        _ => Err("Unknown Property"),
+        "transit" | "culmination" => Ok(Property::Transit),
+        "lowertransit" => Ok(Property::LowerTransit),
+        "transitalt" => Ok(Property::TransitAlt),
+        "airmass" | "airmass:ky" | "airmass:kastenyoung" => {
+            Ok(Property::Airmass(Airmass::KastenYoung))
+        }
+        "airmass:pickering" => Ok(Property::Airmass(Airmass::Pickering)),
+        "airmass:secz" | "airmass:secant" => Ok(Property::Airmass(Airmass::Secant)),
+        "hourangle" | "ha" => Ok(Property::HourAngle),
+        "lst" => Ok(Property::Lst),
+        "gmst" => Ok(Property::Gmst),
+        "eot" => Ok(Property::Eot),
+        "parallactic" => Ok(Property::Parallactic),
+        "sunlit" => Ok(Property::Sunlit),
+        "type" | "kind" => Ok(Property::Kind),
+        "const" | "constellation" => Ok(Property::Constellation),
+        "offset" => Ok(Property::Offset),
+        "moontransit" => Ok(Property::Phenomenon(moons::Phenomenon::Transit)),
+        "shadowtransit" => Ok(Property::Phenomenon(moons::Phenomenon::ShadowTransit)),
+        "occultation" => Ok(Property::Phenomenon(moons::Phenomenon::Occultation)),
+        "eclipse" => Ok(Property::Phenomenon(moons::Phenomenon::Eclipse)),
+        "surfbright" | "sb" => Ok(Property::SurfaceBrightness),
+        "civilbegin" | "civildawn" => Ok(Property::TwilightBegin(Twilight::Civil)),
+        "civilend" | "civildusk" => Ok(Property::TwilightEnd(Twilight::Civil)),
+        "nauticalbegin" | "nauticaldawn" => Ok(Property::TwilightBegin(Twilight::Nautical)),
+        "nauticalend" | "nauticaldusk" => Ok(Property::TwilightEnd(Twilight::Nautical)),
+        "astrobegin" | "astrodawn" => Ok(Property::TwilightBegin(Twilight::Astronomical)),
+        "astroend" | "astrodusk" => Ok(Property::TwilightEnd(Twilight::Astronomical)),
+        "goldenbegin" => Ok(Property::TwilightBegin(Twilight::Golden)),
+        "goldenend" => Ok(Property::TwilightEnd(Twilight::Golden)),
+        "bluebegin" => Ok(Property::TwilightBegin(Twilight::Blue)),
+        "blueend" => Ok(Property::TwilightEnd(Twilight::Blue)),
+        _ => Err(Error::UnknownProperty(s.to_string())),
     }
 }
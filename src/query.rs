@@ -1,12 +1,94 @@
 use crate::value::*;
-use pracstro::{moon, sol, time};
+use crate::{catalog, constellation, eclipse, error::Error, event, moons, occultation, sidereal};
+use pracstro::{coord, moon, sol, time};
 use std::fmt;
 
+/// Bands of solar altitude around sunrise and sunset
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Twilight {
+    Civil,
+    Nautical,
+    Astronomical,
+    Golden,
+    Blue,
+}
+impl Twilight {
+    /// Lowest and highest altitude of the sun in this twilight, in degrees
+    pub fn band(self) -> (f64, f64) {
+        match self {
+            Twilight::Civil => (-6.0, -0.8333),
+            Twilight::Nautical => (-12.0, -6.0),
+            Twilight::Astronomical => (-18.0, -12.0),
+            Twilight::Golden => (-4.0, 6.0),
+            Twilight::Blue => (-6.0, -4.0),
+        }
+    }
+}
+impl fmt::Display for Twilight {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Twilight::Civil => "Civil Twilight",
+                Twilight::Nautical => "Nautical Twilight",
+                Twilight::Astronomical => "Astro. Twilight",
+                Twilight::Golden => "Golden Hour",
+                Twilight::Blue => "Blue Hour",
+            }
+        )
+    }
+}
+
+/// Formulas for the airmass, the length of the path through the atmosphere relative to the zenith
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Airmass {
+    /// Kasten and Young (1989), from the geometric altitude
+    KastenYoung,
+    /// Pickering (2002), from the apparent altitude
+    Pickering,
+    /// The secant of the zenith angle, for a flat atmosphere
+    Secant,
+}
+impl Airmass {
+    /// The airmass at a geometric altitude `h`, and apparent altitude `apparent`, in degrees
+    pub fn at(self, h: f64, apparent: f64) -> f64 {
+        match self {
+            Airmass::KastenYoung => {
+                1.0 / (h.to_radians().sin() + 0.50572 * (h + 6.07995).powf(-1.6364))
+            }
+            Airmass::Pickering => {
+                1.0 / (apparent + 244.0 / (165.0 + 47.0 * apparent.powf(1.1)))
+                    .to_radians()
+                    .sin()
+            }
+            Airmass::Secant => 1.0 / h.to_radians().sin(),
+        }
+    }
+}
+
+/// Where rectangular coordinates are measured from
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Center {
+    Sun,
+    /// The earth, or the planet given by [`RefFrame::from`]
+    Earth,
+}
+
+/// The plane the x and y axes of rectangular coordinates are in, at J2000. x points to the equinox
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Plane {
+    Equatorial,
+    Ecliptic,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum Property {
     Equatorial,
     Horizontal,
     Ecliptic,
+    Galactic,
+    Supergalactic,
     Distance,
     Magnitude,
     PhaseDefault,
@@ -14,8 +96,61 @@ pub enum Property {
     PhaseEmoji,
     AngDia,
     IllumFrac,
+    /// The sun-object-observer angle, 0° when full
+    PhaseAngle,
+    /// Angle from the sun, east or west
+    Elongation,
+    /// Position angle of the midpoint of the bright limb, from the north through the east
+    BrightLimb,
     Rise,
     Set,
+    Transit,
+    LowerTransit,
+    TransitAlt,
+    Airmass(Airmass),
+    /// Hours west of the meridian
+    HourAngle,
+    /// The angle between the direction to the zenith and to the north celestial pole, in degrees
+    Parallactic,
+    /// Local mean sidereal time
+    Lst,
+    /// Greenwich mean sidereal time
+    Gmst,
+    /// Equation of time, apparent less mean solar time
+    Eot,
+    /// If the object is in sunlight, rather than eclipsed
+    Sunlit,
+    /// What kind of deep sky object it is
+    Kind,
+    SurfaceBrightness,
+    /// The IAU constellation the object is in
+    Constellation,
+    /// Offset of a moon from its planet
+    Offset,
+    /// Position in x, y, and z, for objects in the solar system
+    Rectangular(Center, Plane, DistUnit),
+    /// The next start of a transit, shadow transit, occultation, or eclipse of a moon
+    Phenomenon(moons::Phenomenon),
+    /// Details of the next eclipse of the sun or moon
+    Eclipse(eclipse::Circumstance),
+    /// The next aspect of a planet, like opposition or greatest elongation
+    Aspect,
+    /// The next time a planet reaches an aspect
+    NextAspect(event::Aspect),
+    /// The next time the object is closest to another
+    NextConjunction(CelObj),
+    /// Details of the next occultation of one of the stars by the moon or a planet
+    Occultation(occultation::Circumstance, occultation::Stars),
+    /// The next equinox, solstice, or other term the sun reaches
+    Term(event::Terms),
+    /// The next time the sun reaches one of the terms
+    NextTerm(event::Terms),
+    /// For civil, nautical and astronomical twilight, the morning start of twilight (dawn).
+    /// For golden and blue hour, the start of the next one.
+    TwilightBegin(Twilight),
+    /// For civil, nautical and astronomical twilight, the evening end of twilight (dusk).
+    /// For golden and blue hour, the end of the next one.
+    TwilightEnd(Twilight),
     AngBet(CelObj),
 }
 impl fmt::Display for Property {
@@ -27,54 +162,385 @@ impl fmt::Display for Property {
                 Property::Equatorial => "Coordinates (RA/De)",
                 Property::Horizontal => "Coordinates (Azi/Alt)",
                 Property::Ecliptic => "Coordinates (Ecliptic)",
+                Property::Galactic => "Coordinates (Galactic)",
+                Property::Supergalactic => "Coordinates (Supergal.)",
                 Property::Distance => "Distance",
                 Property::Magnitude => "Magnitude",
                 Property::PhaseDefault => "Phase",
                 Property::PhaseEmoji => "Phase Emoji",
                 Property::PhaseName => "Phase Name",
                 Property::IllumFrac => "Illuminated Frac.",
+                Property::PhaseAngle => "Phase Angle",
+                Property::Elongation => "Elongation",
+                Property::BrightLimb => "Bright Limb Angle",
                 Property::AngDia => "Angular Diameter",
                 Property::Rise => "Rise Time",
                 Property::Set => "Set Time",
+                Property::Transit => "Transit Time",
+                Property::LowerTransit => "Lower Transit Time",
+                Property::TransitAlt => "Transit Altitude",
+                Property::Airmass(Airmass::KastenYoung) => "Airmass",
+                Property::Airmass(Airmass::Pickering) => "Airmass (Pickering)",
+                Property::Airmass(Airmass::Secant) => "Airmass (sec z)",
+                Property::HourAngle => "Hour Angle (h)",
+                Property::Parallactic => "Parallactic Angle (°)",
+                Property::Lst => "Local Sidereal Time",
+                Property::Gmst => "Greenwich Sidereal Time",
+                Property::Eot => "Equation of Time",
+                Property::Sunlit => "Sunlit",
+                Property::Kind => "Type",
+                Property::SurfaceBrightness => "Surface Brightness",
+                Property::Constellation => "Constellation",
+                Property::Offset => "Offset (E/N)",
+                Property::Rectangular(c, p, u) => {
+                    return write!(
+                        f,
+                        "{} XYZ ({}, {})",
+                        match c {
+                            Center::Sun => "Heliocentric",
+                            Center::Earth => "Geocentric",
+                        },
+                        match p {
+                            Plane::Equatorial => "Equatorial",
+                            Plane::Ecliptic => "Ecliptic",
+                        },
+                        match u {
+                            DistUnit::Au => "AU",
+                            DistUnit::Km => "km",
+                        }
+                    );
+                }
+                Property::Phenomenon(moons::Phenomenon::Transit) => "Moon Transit",
+                Property::Phenomenon(moons::Phenomenon::ShadowTransit) => "Shadow Transit",
+                Property::Phenomenon(moons::Phenomenon::Occultation) => "Occultation",
+                Property::Phenomenon(moons::Phenomenon::Eclipse) => "Eclipse",
+                Property::Eclipse(c) => match c {
+                    eclipse::Circumstance::Kind => "Eclipse Type",
+                    eclipse::Circumstance::Magnitude => "Eclipse Mag.",
+                    eclipse::Circumstance::Gamma => "Gamma",
+                    eclipse::Circumstance::Begin => "Eclipse Begins",
+                    eclipse::Circumstance::TotalityBegin => "Totality Begins",
+                    eclipse::Circumstance::Maximum => "Greatest Eclipse",
+                    eclipse::Circumstance::TotalityEnd => "Totality Ends",
+                    eclipse::Circumstance::End => "Eclipse Ends",
+                    eclipse::Circumstance::Obscuration => "Obscuration",
+                    eclipse::Circumstance::BeginAltitude => "Altitude at Begin",
+                    eclipse::Circumstance::MaximumAltitude => "Altitude at Maximum",
+                    eclipse::Circumstance::EndAltitude => "Altitude at End",
+                },
+                Property::TwilightBegin(t) => return write!(f, "{t} Begin"),
+                Property::TwilightEnd(t) => return write!(f, "{t} End"),
+                Property::Aspect => "Aspect",
+                Property::NextAspect(a) => return write!(f, "Next {a}"),
+                Property::NextConjunction(_) => "Next Conjunction",
+                Property::Occultation(c, _) => match c {
+                    occultation::Circumstance::Star => "Occulted Star",
+                    occultation::Circumstance::Conjunction => "Conjunction",
+                    occultation::Circumstance::Separation => "Separation (Dec.)",
+                    occultation::Circumstance::Disappearance => "Disappears",
+                    occultation::Circumstance::DisappearanceAngle => "Disappearance PA",
+                    occultation::Circumstance::DisappearanceLimb => "Disappearance Limb",
+                    occultation::Circumstance::Reappearance => "Reappears",
+                    occultation::Circumstance::ReappearanceAngle => "Reappearance PA",
+                    occultation::Circumstance::ReappearanceLimb => "Reappearance Limb",
+                },
+                Property::Term(t) | Property::NextTerm(t) => {
+                    let name = match t {
+                        event::Terms::Seasons => "Season",
+                        event::Terms::CrossQuarter => "Cross-Quarter",
+                        event::Terms::Solar => "Solar Term",
+                    };
+                    return match self {
+                        Property::NextTerm(_) => write!(f, "Next {name}"),
+                        _ => write!(f, "{name}"),
+                    };
+                }
                 Property::AngBet(_) => "Angle Between Object",
             }
         )
     }
 }
 
-pub fn property_of(obj: &CelObj, q: Property, rf: &RefFrame) -> Result<Value, &'static str> {
-    fn hemisphere(ll: Option<(pracstro::time::Angle, pracstro::time::Angle)>) -> bool {
-        if let Some((lat, _)) = ll {
+/// The obliquity pracstro turns ecliptic positions of planets to equatorial ones with
+const OBLIQUITY_J2000: f64 = 23.43928;
+
+/// Angular diameter of the earth at 1 AU in degrees. pracstro's [`sol::EARTH`] doesn't have a real one
+const EARTH_THETA0: f64 = 0.004_88;
+/// Magnitude of the earth at 1 AU from the sun and the observer, when full
+const EARTH_V0: f64 = -3.99;
+
+/// Position of the object relative to the sun in AU, on J2000 equatorial axes, for objects in the solar system
+///
+/// Stars and deep sky objects look the same from any planet, and satellites can only be seen from
+/// the earth, so they have none.
+pub fn heliocentric(obj: &CelObj, d: time::Date) -> Option<(f64, f64, f64)> {
+    match obj {
+        CelObj::Planet(p) => Some(p.locationcart(d)),
+        CelObj::Sun => Some((0.0, 0.0, 0.0)),
+        // Not from moon::MOON.locationcart, which takes the declination for the angle from the pole
+        CelObj::Moon => {
+            let (ra, de) = moon::MOON.location(d).equatorial();
+            let (e, dist) = (sol::EARTH.locationcart(d), moon::MOON.distance(d));
+            Some((
+                e.0 + dist * de.cos() * ra.cos(),
+                e.1 + dist * de.cos() * ra.sin(),
+                e.2 + dist * de.sin(),
+            ))
+        }
+        CelObj::Minor(m) => Some(m.locationcart(d)),
+        CelObj::PlanetMoon(m) => Some(m.locationcart(d)),
+        _ => None,
+    }
+}
+
+/// Properties of an object in the solar system, from the vector between it and the planet it's seen from
+///
+/// Directions are on the earth's J2000 equator, like [`sol::Planet::location`], wherever they're seen from.
+fn planetocentric(obj: &CelObj, q: Property, rf: &RefFrame) -> Result<Value, Error> {
+    fn norm(v: (f64, f64, f64)) -> f64 {
+        (v.0 * v.0 + v.1 * v.1 + v.2 * v.2).sqrt()
+    }
+    let d = rf.date;
+    let h = heliocentric(obj, d).expect("Object in the solar system");
+    let o = rf.from.unwrap_or(&sol::EARTH).locationcart(d);
+    let v = (h.0 - o.0, h.1 - o.1, h.2 - o.2);
+    let (r, delta) = (norm(h), norm(v));
+    // The sun-object-observer angle, and the fraction of the disk that's lit
+    let sunangle =
+        || time::Angle::acos(((h.0 * v.0 + h.1 * v.1 + h.2 * v.2) / (r * delta)).clamp(-1.0, 1.0));
+    let illumfrac = || 0.5 * (1.0 + sunangle().cos());
+
+    match (q.clone(), obj) {
+        (Property::Equatorial, _) => Ok(Value::Crd(
+            topocentric(coord::Coord::from_cartesian(v.0, v.1, v.2), delta, rf),
+            CrdView::Equatorial,
+        )),
+        (Property::Distance, _) => Ok(Value::Dist(delta)),
+        (_, CelObj::Sun) => match q {
+            Property::Magnitude => Ok(Value::Num(5.0 * delta.log10() - 26.74)),
+            Property::AngDia => Ok(Value::Ang(
+                sol::SUN.angdia(d) * (sol::SUN.distance(d) / delta),
+                AngView::Angle,
+            )),
+            _ => Err(Error::Unsupported(q.to_string(), "the sun")),
+        },
+        (Property::PhaseDefault, _) => Ok(Value::Phase(
+            time::Angle::from_degrees(180.0) - sunangle(),
+            PhaseView::Default(false),
+        )),
+        (Property::Magnitude, CelObj::Planet(p)) => {
+            let v0 = if *p == sol::EARTH { EARTH_V0 } else { p.v0 };
+            Ok(Value::Num(
+                5.0 * (r * delta / illumfrac().sqrt()).log10() + v0,
+            ))
+        }
+        (Property::Magnitude, CelObj::Moon) => Ok(Value::Num(
+            5.0 * (r * delta / illumfrac().sqrt()).log10() + 0.21,
+        )),
+        (Property::Magnitude, CelObj::Minor(m)) => {
+            Ok(Value::Num(m.brightness(r, delta, sunangle())))
+        }
+        (Property::Magnitude, CelObj::PlanetMoon(m)) => Ok(Value::Num(m.brightness(r, delta))),
+        (Property::AngDia, CelObj::Planet(p)) => {
+            let theta0 = if *p == sol::EARTH {
+                time::Angle::from_degrees(EARTH_THETA0)
+            } else {
+                p.theta0
+            };
+            Ok(Value::Ang(theta0 / delta, AngView::Angle))
+        }
+        (Property::AngDia, CelObj::Moon) => {
+            Ok(Value::Ang(moon::MOON.theta0 / delta, AngView::Angle))
+        }
+        (Property::AngDia, CelObj::PlanetMoon(m)) => Ok(Value::Ang(
+            m.angdia(d) * (m.distance(d) / delta),
+            AngView::Angle,
+        )),
+        (_, CelObj::Minor(_)) => Err(Error::Unsupported(q.to_string(), "a minor planet or comet")),
+        _ => unreachable!(),
+    }
+}
+
+/// The equinox of the equatorial coordinates of an object
+///
+/// Planets, and everything seen from another planet, are on J2000 axes like [`sol::Planet::location`].
+/// Raw coordinates are taken to be J2000 too, and everything else is of the date.
+fn equinox(obj: &CelObj, rf: &RefFrame) -> time::Date {
+    match obj {
+        CelObj::Planet(_) | CelObj::PlanetMoon(_) | CelObj::Crd(_) => time::J2000,
+        _ if rf.from.is_some() && heliocentric(obj, rf.date).is_some() => time::J2000,
+        _ => rf.date,
+    }
+}
+
+/// Equatorial coordinates of the object on the equator and equinox of the date, whatever frame
+/// it's computed in, so positions of different kinds of objects can be compared
+pub fn of_date(obj: &CelObj, rf: &RefFrame) -> Result<coord::Coord, Error> {
+    let Value::Crd(p, _) = property_of(obj, Property::Equatorial, rf)? else {
+        unreachable!();
+    };
+    Ok(constellation::precess(p, equinox(obj, rf), rf.date))
+}
+
+/// Corrects the geocentric position of an object `dist` AU away for diurnal parallax,
+/// giving its direction as seen by the observer. Without an observer on the earth this does nothing.
+///
+/// Meeus, Astronomical Algorithms, Ch. 40
+pub fn topocentric(c: coord::Coord, dist: f64, rf: &RefFrame) -> coord::Coord {
+    const FLATTENING: f64 = 0.996_647_19; // b/a of the earth
+    const EQUATORIAL_RADIUS: f64 = 6_378_140.0; // Meters
+    let (Some((lat, long, elev)), None) = (rf.latlong, rf.from) else {
+        return c;
+    };
+    let u = (FLATTENING * lat.tan()).atan();
+    let rho_sin = FLATTENING * u.sin() + (elev / EQUATORIAL_RADIUS) * lat.sin();
+    let rho_cos = u.cos() + (elev / EQUATORIAL_RADIUS) * lat.cos();
+    let sin_pi = time::Angle::from_degminsec(0, 0, 8.794).sin() / dist;
+
+    let (ra, de) = c.equatorial();
+    let ha = sidereal::lst(rf.date, long) - ra;
+    let dra = (-rho_cos * sin_pi * ha.sin()).atan2(de.cos() - rho_cos * sin_pi * ha.cos());
+    let topo_de =
+        ((de.sin() - rho_sin * sin_pi) * dra.cos()).atan2(de.cos() - rho_cos * sin_pi * ha.cos());
+    coord::Coord::from_equatorial(
+        ra + time::Angle::from_radians(dra),
+        time::Angle::from_radians(topo_de),
+    )
+}
+
+pub fn property_of(obj: &CelObj, q: Property, rf: &RefFrame) -> Result<Value, Error> {
+    fn hemisphere(ll: Location) -> bool {
+        if let Some((lat, _, _)) = ll {
             lat.to_latitude().degrees() <= 0.0
         } else {
             false
         }
     }
-    match (q, obj.clone()) {
-        (Property::Equatorial, CelObj::Planet(p)) => {
-            Ok(Value::Crd(p.location(rf.date), CrdView::Equatorial))
+    match (q.clone(), obj.clone()) {
+        (_, CelObj::Planet(p)) if p == *rf.from.unwrap_or(&sol::EARTH) => Err(Error::Unsupported(
+            q.to_string(),
+            "the planet it's seen from",
+        )),
+        (
+            Property::Rectangular(center, plane, unit),
+            CelObj::Sun
+            | CelObj::Moon
+            | CelObj::Planet(_)
+            | CelObj::Minor(_)
+            | CelObj::PlanetMoon(_),
+        ) => {
+            let h = heliocentric(obj, rf.date).expect("Object in the solar system");
+            let o = match center {
+                Center::Sun => (0.0, 0.0, 0.0),
+                Center::Earth => rf.from.unwrap_or(&sol::EARTH).locationcart(rf.date),
+            };
+            let (x, y, z) = (h.0 - o.0, h.1 - o.1, h.2 - o.2);
+            Ok(Value::Xyz(
+                match plane {
+                    Plane::Equatorial => (x, y, z),
+                    Plane::Ecliptic => {
+                        let eps = OBLIQUITY_J2000.to_radians();
+                        (
+                            x,
+                            eps.cos() * y + eps.sin() * z,
+                            eps.cos() * z - eps.sin() * y,
+                        )
+                    }
+                },
+                unit,
+            ))
+        }
+        // These need an observer on the earth
+        (
+            Property::Horizontal
+            | Property::Rise
+            | Property::Set
+            | Property::Transit
+            | Property::LowerTransit
+            | Property::TransitAlt
+            | Property::Airmass(_)
+            | Property::HourAngle
+            | Property::Parallactic
+            | Property::Lst
+            | Property::Gmst
+            | Property::Eot
+            | Property::TwilightBegin(_)
+            | Property::TwilightEnd(_)
+            | Property::Offset
+            | Property::Phenomenon(_)
+            | Property::Eclipse(_)
+            | Property::Occultation(..)
+            | Property::Term(_)
+            | Property::NextTerm(_),
+            _,
+        ) if rf.from.is_some() => Err(Error::Unsupported(
+            q.to_string(),
+            "anything seen from another planet",
+        )),
+        (_, CelObj::Satellite(_)) if rf.from.is_some() => Err(Error::Unsupported(
+            q.to_string(),
+            "a satellite seen from another planet",
+        )),
+        (
+            Property::Equatorial
+            | Property::Distance
+            | Property::Magnitude
+            | Property::PhaseDefault
+            | Property::AngDia,
+            CelObj::Sun
+            | CelObj::Moon
+            | CelObj::Planet(_)
+            | CelObj::Minor(_)
+            | CelObj::PlanetMoon(_),
+        ) if rf.from.is_some() => planetocentric(obj, q, rf),
+        (Property::Equatorial | Property::Distance, CelObj::Planet(_) | CelObj::PlanetMoon(_)) => {
+            planetocentric(obj, q, rf)
         }
         (Property::Equatorial, CelObj::Sun) => Ok(Value::Crd(
-            sol::SUN
-                .location(rf.date)
-                .precess(time::Date::from_julian(2451545.0), rf.date),
+            topocentric(
+                sol::SUN
+                    .location(rf.date)
+                    .precess(time::Date::from_julian(2451545.0), rf.date),
+                sol::SUN.distance(rf.date),
+                rf,
+            ),
             CrdView::Equatorial,
         )),
         (Property::Equatorial, CelObj::Moon) => Ok(Value::Crd(
-            moon::MOON
-                .location(rf.date)
-                .precess(time::Date::from_julian(2451545.0), rf.date),
+            topocentric(
+                moon::MOON
+                    .location(rf.date)
+                    .precess(time::Date::from_julian(2451545.0), rf.date),
+                moon::MOON.distance(rf.date),
+                rf,
+            ),
+            CrdView::Equatorial,
+        )),
+        (Property::Equatorial, CelObj::Star(s)) => {
+            Ok(Value::Crd(s.location(rf.date), CrdView::Equatorial))
+        }
+        (Property::Equatorial, CelObj::DeepSky(s)) => {
+            Ok(Value::Crd(s.location(rf.date), CrdView::Equatorial))
+        }
+        (Property::Offset, CelObj::PlanetMoon(m)) => {
+            let (e, n) = m.offset(rf.date);
+            Ok(Value::Offset(e, n))
+        }
+        (Property::Phenomenon(ph), CelObj::PlanetMoon(m)) => {
+            Ok(Value::Event(event::next_phenomenon(m, rf, ph)?))
+        }
+        (Property::Equatorial, CelObj::Minor(m)) => Ok(Value::Crd(
+            topocentric(m.location(rf.date), m.distance(rf.date), rf),
             CrdView::Equatorial,
         )),
-        (Property::Equatorial, CelObj::Star(s)) => Ok(Value::Crd(
-            s.loc_j2k
-                .precess(time::Date::from_julian(2451545.0), rf.date),
+        (Property::Equatorial, CelObj::Satellite(s)) => Ok(Value::Crd(
+            s.location(rf.date, rf.latlong)?,
             CrdView::Equatorial,
         )),
         (Property::Equatorial, CelObj::Crd(s)) => Ok(Value::Crd(s, CrdView::Equatorial)),
         (Property::Horizontal, _) => {
             if rf.latlong.is_none() {
-                return Err("Need to specify a lat/long with -l");
+                return Err(Error::MissingLocation);
             };
             let Value::Crd(p, _) = property_of(obj, Property::Equatorial, rf)? else {
                 unreachable!();
@@ -87,51 +553,155 @@ pub fn property_of(obj: &CelObj, q: Property, rf: &RefFrame) -> Result<Value, &'
             };
             Ok(Value::Crd(p, CrdView::Ecliptic(rf.date)))
         }
-        (Property::Rise, _) => {
-            if rf.latlong.is_none() {
-                return Err("Need to specify a lat/long with -l");
-            };
+        // These don't depend on the object, and are usually asked of the site
+        (Property::Lst, _) => {
+            let (_, long, _) = rf.latlong.ok_or(Error::MissingLocation)?;
+            Ok(Value::Ang(sidereal::lst(rf.date, long), AngView::Time))
+        }
+        (Property::Gmst, _) => Ok(Value::Ang(sidereal::gmst(rf.date), AngView::Time)),
+        (Property::Eot, _) => Ok(Value::Ang(
+            sidereal::equation_of_time(rf.date),
+            AngView::SignedTime,
+        )),
+        // Objects below the horizon have no airmass
+        (Property::Airmass(model), _) => {
+            let alt = event::altitude(obj, rf)?;
+            let apparent = rf
+                .refraction
+                .apparent(time::Angle::from_degrees(alt))
+                .to_latitude()
+                .degrees();
+            Ok(Value::Airmass(
+                (apparent > 0.0).then(|| model.at(alt, apparent)),
+            ))
+        }
+        (Property::HourAngle, _) => Ok(Value::Num(
+            event::hour_angle(obj, rf)?.to_latitude().degrees() / 15.0,
+        )),
+        // Meeus, Astronomical Algorithms, Ch. 14
+        (Property::Parallactic, _) => {
+            let (lat, _, _) = rf.latlong.ok_or(Error::MissingLocation)?;
+            let ha = event::hour_angle(obj, rf)?;
             let Value::Crd(p, _) = property_of(obj, Property::Equatorial, rf)? else {
                 unreachable!();
             };
-            match p.riseset(rf.date, rf.latlong.unwrap().0, rf.latlong.unwrap().1) {
-                Some((x, _)) => Ok(Value::RsTime(Some(time::Date::from_time(rf.date, x)))),
-                None => Ok(Value::RsTime(None)),
-            }
+            let de = p.equatorial().1;
+            Ok(Value::Num(
+                ha.sin()
+                    .atan2(lat.tan() * de.cos() - de.sin() * ha.cos())
+                    .to_degrees(),
+            ))
         }
-        (Property::Set, _) => {
-            if rf.latlong.is_none() {
-                return Err("Need to specify a lat/long with -l");
-            };
+        (Property::Galactic, _) => {
             let Value::Crd(p, _) = property_of(obj, Property::Equatorial, rf)? else {
                 unreachable!();
             };
-            match p.riseset(rf.date, rf.latlong.unwrap().0, rf.latlong.unwrap().1) {
-                Some((_, y)) => Ok(Value::RsTime(Some(time::Date::from_time(rf.date, y)))),
-                None => Ok(Value::RsTime(None)),
-            }
+            Ok(Value::Crd(p, CrdView::Galactic(equinox(obj, rf))))
         }
-        (Property::AngBet(c), _) => {
+        (Property::Supergalactic, _) => {
             let Value::Crd(p, _) = property_of(obj, Property::Equatorial, rf)? else {
                 unreachable!();
             };
-            let Value::Crd(o, _) = property_of(&c, Property::Equatorial, rf)? else {
+            Ok(Value::Crd(p, CrdView::Supergalactic(equinox(obj, rf))))
+        }
+        (Property::Constellation, _) => {
+            let Value::Crd(p, _) = property_of(obj, Property::Equatorial, rf)? else {
                 unreachable!();
             };
-            Ok(Value::Ang(p.dist(o), AngView::Angle))
+            let abbr = constellation::abbreviation(p, equinox(obj, rf));
+            Ok(Value::Text(format!(
+                "{abbr} ({})",
+                constellation::name(abbr)
+            )))
         }
-        (Property::Distance, CelObj::Planet(p)) => Ok(Value::Dist(p.distance(rf.date))),
+        (Property::Rise, _) => Ok(Value::RsTime(event::next_rise(obj, rf)?)),
+        (Property::Set, _) => Ok(Value::RsTime(event::next_set(obj, rf)?)),
+        // Satellites culminate in the middle of a pass, rather than at the meridian
+        (Property::Transit, CelObj::Satellite(_)) => Ok(Value::RsTime(
+            event::passes(obj, rf, event::window_end(rf))?
+                .first()
+                .map(|p| p.culmination),
+        )),
+        (Property::TransitAlt, CelObj::Satellite(_)) => {
+            match event::passes(obj, rf, event::window_end(rf))?.first() {
+                Some(p) => Ok(Value::Ang(
+                    rf.refraction.apparent(time::Angle::from_degrees(p.max_alt)),
+                    AngView::Latitude,
+                )),
+                None => Err(Error::Unsupported(
+                    q.to_string(),
+                    "a satellite that doesn't pass overhead",
+                )),
+            }
+        }
+        (Property::Transit, _) => Ok(Value::RsTime(event::next_transit(obj, rf, true)?)),
+        (Property::LowerTransit, _) => Ok(Value::RsTime(event::next_transit(obj, rf, false)?)),
+        (Property::TransitAlt, _) => {
+            let date = event::next_transit(obj, rf, true)?.unwrap_or(rf.date);
+            let alt = event::altitude(obj, &RefFrame { date, ..*rf })?;
+            Ok(Value::Ang(
+                rf.refraction.apparent(time::Angle::from_degrees(alt)),
+                AngView::Latitude,
+            ))
+        }
+        (Property::TwilightBegin(t), CelObj::Sun) => {
+            let (lo, hi) = t.band();
+            Ok(Value::RsTime(match t {
+                Twilight::Golden | Twilight::Blue => next_band(obj, rf, lo, hi)?.0,
+                _ => event::next_altitude(obj, rf, lo, true)?,
+            }))
+        }
+        (Property::TwilightEnd(t), CelObj::Sun) => {
+            let (lo, hi) = t.band();
+            Ok(Value::RsTime(match t {
+                Twilight::Golden | Twilight::Blue => next_band(obj, rf, lo, hi)?.1,
+                _ => event::next_altitude(obj, rf, lo, false)?,
+            }))
+        }
+        (Property::AngBet(c), _) => Ok(Value::Ang(
+            of_date(obj, rf)?.dist(of_date(&c, rf)?),
+            AngView::Angle,
+        )),
         (Property::Distance, CelObj::Sun) => Ok(Value::Dist(sol::SUN.distance(rf.date))),
         (Property::Distance, CelObj::Moon) => Ok(Value::Dist(moon::MOON.distance(rf.date))),
-        (Property::Distance, CelObj::Star(s)) => {
-            Ok(Value::Dist((1.0 / (s.pi.degrees() * 3600.0)) * 206_265.0))
+        (Property::Magnitude, CelObj::PlanetMoon(m)) => Ok(Value::Num(m.magnitude(rf.date))),
+        // Seen from the earth, the moons are lit the same way as their planet
+        (Property::PhaseDefault, CelObj::PlanetMoon(m)) => Ok(Value::Phase(
+            m.planet().phaseangle(rf.date),
+            PhaseView::Default(hemisphere(rf.latlong)),
+        )),
+        (Property::AngDia, CelObj::PlanetMoon(m)) => {
+            Ok(Value::Ang(m.angdia(rf.date), AngView::Angle))
         }
+        (Property::Distance, CelObj::Minor(m)) => Ok(Value::Dist(m.distance(rf.date))),
+        (Property::Distance, CelObj::Satellite(s)) => {
+            Ok(Value::Dist(s.distance(rf.date, rf.latlong)?))
+        }
+        (Property::Sunlit, CelObj::Satellite(s)) => Ok(Value::Bool(s.sunlit(rf.date)?)),
+        (Property::Distance, CelObj::Star(s)) => Ok(Value::Dist(s.distance(rf.date))),
         (Property::Magnitude, CelObj::Planet(p)) => Ok(Value::Num(p.magnitude(rf.date))),
+        (Property::Magnitude, CelObj::Minor(m)) => Ok(Value::Num(m.magnitude(rf.date))),
         (Property::Magnitude, CelObj::Star(s)) => Ok(Value::Num(s.mag)),
+        (Property::Magnitude, CelObj::DeepSky(s)) => s
+            .mag
+            .map(Value::Num)
+            .ok_or(Error::Unsupported(q.to_string(), "a dark nebula")),
+        (Property::SurfaceBrightness, CelObj::DeepSky(s)) => s
+            .surface_brightness()
+            .map(Value::Num)
+            .ok_or(Error::Unsupported(q.to_string(), "a dark nebula")),
+        (Property::Kind, CelObj::DeepSky(s)) => Ok(Value::Text(s.kind.to_string())),
         (Property::Magnitude, CelObj::Sun) => Ok(Value::Num(sol::SUN.magnitude(rf.date))),
         (Property::Magnitude, CelObj::Moon) => Ok(Value::Num(moon::MOON.magnitude(rf.date))),
-        (Property::PhaseDefault, CelObj::Planet(p)) => Ok(Value::Phase(
-            p.phaseangle(rf.date),
+        // Not from sol::Planet::phaseangle, which takes an arcsine and so folds it wrong for Mercury and Venus
+        (Property::PhaseDefault, CelObj::Planet(_)) => {
+            let Value::Phase(p, _) = planetocentric(obj, q, rf)? else {
+                unreachable!();
+            };
+            Ok(Value::Phase(p, PhaseView::Default(hemisphere(rf.latlong))))
+        }
+        (Property::PhaseDefault, CelObj::Minor(m)) => Ok(Value::Phase(
+            m.phaseangle(rf.date),
             PhaseView::Default(hemisphere(rf.latlong)),
         )),
         (Property::PhaseDefault, CelObj::Moon) => Ok(Value::Phase(
@@ -161,36 +731,1003 @@ pub fn property_of(obj: &CelObj, q: Property, rf: &RefFrame) -> Result<Value, &'
             };
             Ok(Value::Phase(p, PhaseView::Illumfrac))
         }
+        (Property::PhaseAngle, _) => {
+            let Value::Phase(p, _) = property_of(obj, Property::PhaseDefault, rf)? else {
+                unreachable!();
+            };
+            Ok(Value::Ang(
+                time::Angle::from_degrees((180.0 - p.degrees()).abs()),
+                AngView::Angle,
+            ))
+        }
+        (Property::Elongation | Property::BrightLimb, CelObj::Sun) => {
+            Err(Error::Unsupported(q.to_string(), "the sun"))
+        }
+        (Property::Elongation, _) => {
+            let (p, s) = (of_date(obj, rf)?, of_date(&CelObj::Sun, rf)?);
+            let east = (p.ecliptic(rf.date).0 - s.ecliptic(rf.date).0).degrees() < 180.0;
+            Ok(Value::Ang(
+                p.dist(s) * if east { 1.0 } else { -1.0 },
+                AngView::Elongation,
+            ))
+        }
+        // Meeus, Astronomical Algorithms, Ch. 48
+        (
+            Property::BrightLimb,
+            CelObj::Moon | CelObj::Planet(_) | CelObj::Minor(_) | CelObj::PlanetMoon(_),
+        ) => {
+            let (p, s) = (of_date(obj, rf)?, of_date(&CelObj::Sun, rf)?);
+            let ((ra, de), (ra0, de0)) = (p.equatorial(), s.equatorial());
+            Ok(Value::Ang(
+                time::Angle::atan2(
+                    de0.cos() * (ra0 - ra).sin(),
+                    de0.sin() * de.cos() - de0.cos() * de.sin() * (ra0 - ra).cos(),
+                ),
+                AngView::Angle,
+            ))
+        }
+        (Property::Eclipse(c), CelObj::Sun) => {
+            eclipse_value(&eclipse::next(eclipse::Body::Sun, rf.date), c, rf)
+        }
+        (Property::Eclipse(c), CelObj::Moon) => {
+            eclipse_value(&eclipse::next(eclipse::Body::Moon, rf.date), c, rf)
+        }
+        (Property::Aspect, _) => Ok(Value::Text(
+            event::next_aspect(obj, rf, None)?.map_or("none".to_string(), |(_, a)| a.to_string()),
+        )),
+        (Property::NextAspect(a), _) => Ok(Value::Event(
+            event::next_aspect(obj, rf, Some(a))?.map(|(d, _)| d),
+        )),
+        (Property::NextConjunction(c), _) => {
+            Ok(Value::Event(event::next_conjunction(obj, &c, rf)?))
+        }
+        (Property::Occultation(c, stars), CelObj::Moon | CelObj::Planet(_)) => {
+            let next = occultation::next(obj, &stars, rf, OCCULTATION_WINDOW)?;
+            occultation_value(next.as_ref(), c, rf)
+        }
+        (Property::Term(t), CelObj::Sun) => Ok(Value::Text(event::next_term(t, rf)?.1.to_string())),
+        (Property::NextTerm(t), CelObj::Sun) => Ok(Value::Event(Some(event::next_term(t, rf)?.0))),
+        (Property::Term(_) | Property::NextTerm(_), _) => {
+            Err(Error::Unsupported(q.to_string(), "anything but the sun"))
+        }
+        (Property::Occultation(..), _) => Err(Error::Unsupported(
+            q.to_string(),
+            "anything but the moon or a planet",
+        )),
+        (Property::Eclipse(_), _) => Err(Error::Unsupported(
+            q.to_string(),
+            "anything but the sun or moon",
+        )),
         (Property::AngDia, CelObj::Planet(p)) => Ok(Value::Ang(p.angdia(rf.date), AngView::Angle)),
         (Property::AngDia, CelObj::Sun) => Ok(Value::Ang(sol::SUN.angdia(rf.date), AngView::Angle)),
         (Property::AngDia, CelObj::Moon) => {
             Ok(Value::Ang(moon::MOON.angdia(rf.date), AngView::Angle))
         }
-        (Property::PhaseDefault, _) => Err("Can't get phase of a star"),
-        (_, CelObj::Crd(_)) => Err("Can't get that property for a raw coordinate"),
-        (Property::AngDia, CelObj::Star(_)) => Err("Angular diameter of star not known"),
+        // The major axis, for objects that aren't round
+        (Property::AngDia, CelObj::DeepSky(s)) => Ok(Value::Ang(
+            time::Angle::from_degrees(s.size.0 / 60.0),
+            AngView::Angle,
+        )),
+        (Property::TwilightBegin(_) | Property::TwilightEnd(_), _) => {
+            Err(Error::Unsupported(q.to_string(), "anything but the sun"))
+        }
+        (Property::Sunlit, _) => Err(Error::Unsupported(
+            q.to_string(),
+            "anything but a satellite",
+        )),
+        (Property::Kind | Property::SurfaceBrightness, _) => Err(Error::Unsupported(
+            q.to_string(),
+            "anything but a deep sky object",
+        )),
+        (Property::Offset | Property::Phenomenon(_), _) => Err(Error::Unsupported(
+            q.to_string(),
+            "anything but a moon of Jupiter or Saturn",
+        )),
+        (_, CelObj::DeepSky(_)) => Err(Error::Unsupported(q.to_string(), "a deep sky object")),
+        (_, CelObj::Crd(_)) => Err(Error::Unsupported(q.to_string(), "a raw coordinate")),
+        (_, CelObj::Site) => Err(Error::Unsupported(q.to_string(), "the observer's site")),
+        (_, CelObj::Star(_)) => Err(Error::Unsupported(q.to_string(), "a star")),
+        (_, CelObj::Minor(_)) => Err(Error::Unsupported(q.to_string(), "a minor planet or comet")),
+        (_, CelObj::Satellite(_)) => Err(Error::Unsupported(q.to_string(), "a satellite")),
+        (_, CelObj::Sun) => Err(Error::Unsupported(q.to_string(), "the sun")),
     }
 }
 
+/// The next time the object enters the band of altitudes between `lo` and `hi`, and the time it leaves
+///
+/// It enters from above in the evening, and leaves through the bottom, and the reverse in the morning.
+fn next_band(
+    obj: &CelObj,
+    rf: &RefFrame,
+    lo: f64,
+    hi: f64,
+) -> Result<(Option<time::Date>, Option<time::Date>), Error> {
+    let from_above = event::next_altitude(obj, rf, hi, false)?;
+    let from_below = event::next_altitude(obj, rf, lo, true)?;
+    let (enter, rising) = match (from_above, from_below) {
+        (Some(a), Some(b)) if b.julian() < a.julian() => (b, true),
+        (Some(a), _) => (a, false),
+        (None, Some(b)) => (b, true),
+        (None, None) => return Ok((None, None)),
+    };
+    let leave = event::next_altitude(
+        obj,
+        &RefFrame { date: enter, ..*rf },
+        if rising { hi } else { lo },
+        rising,
+    )?;
+    Ok((Some(enter), leave))
+}
+
 /// An object and a CSV list of properties. The return stack is these properties.
-pub fn run(
-    object: &CelObj,
-    proplist: &[Property],
-    latlong: Location,
-    date: time::Date,
-) -> Result<Vec<Value>, &'static str> {
-    Ok(proplist
+pub fn run(object: &CelObj, proplist: &[Property], rf: &RefFrame) -> Result<Vec<Value>, Error> {
+    proplist
         .iter()
-        .map(|prop| {
-            property_of(
-                object,
-                prop.clone(),
-                &RefFrame {
-                    latlong,
-                    date,
-                },
+        .map(|prop| property_of(object, prop.clone(), rf))
+        .collect()
+}
+
+/// A detail of an eclipse. For solar eclipses, contact times are those seen by the observer
+fn eclipse_value(
+    e: &eclipse::Eclipse,
+    c: eclipse::Circumstance,
+    rf: &RefFrame,
+) -> Result<Value, Error> {
+    use eclipse::{Body, Circumstance};
+    let local = || eclipse::local(e, rf);
+    Ok(match (c, e.body) {
+        (Circumstance::Kind, _) => Value::Text(e.kind.to_string()),
+        (Circumstance::Magnitude, _) => Value::Ratio(e.magnitude),
+        (Circumstance::Gamma, _) => Value::Ratio(e.gamma),
+        (Circumstance::Maximum, Body::Sun) if rf.latlong.is_some() => {
+            Value::Event(local()?.map(|l| l.maximum))
+        }
+        (Circumstance::Maximum, _) => Value::Event(Some(e.greatest)),
+        (Circumstance::Begin, Body::Sun) => Value::Event(local()?.map(|l| l.begin)),
+        (Circumstance::End, Body::Sun) => Value::Event(local()?.map(|l| l.end)),
+        (Circumstance::TotalityBegin, Body::Sun) => {
+            Value::Event(local()?.and_then(|l| l.central).map(|t| t.0))
+        }
+        (Circumstance::TotalityEnd, Body::Sun) => {
+            Value::Event(local()?.and_then(|l| l.central).map(|t| t.1))
+        }
+        (Circumstance::Obscuration, Body::Sun) => {
+            Value::Num(100.0 * local()?.map_or(0.0, |l| l.obscuration))
+        }
+        (Circumstance::Begin, Body::Moon) => Value::Event(Some(e.contacts().0)),
+        (Circumstance::End, Body::Moon) => Value::Event(Some(e.contacts().1)),
+        (Circumstance::TotalityBegin, Body::Moon) => Value::Event(e.totality().map(|t| t.0)),
+        (Circumstance::TotalityEnd, Body::Moon) => Value::Event(e.totality().map(|t| t.1)),
+        (Circumstance::Obscuration, Body::Moon) => {
+            return Err(Error::Unsupported(
+                Property::Eclipse(c).to_string(),
+                "a lunar eclipse",
+            ))
+        }
+        (
+            Circumstance::BeginAltitude | Circumstance::MaximumAltitude | Circumstance::EndAltitude,
+            _,
+        ) if rf.latlong.is_none() => return Err(Error::MissingLocation),
+        (Circumstance::BeginAltitude, Body::Sun) => {
+            return eclipse_altitude(e, local()?.map(|l| l.begin), rf)
+        }
+        (Circumstance::MaximumAltitude, Body::Sun) => {
+            return eclipse_altitude(e, local()?.map(|l| l.maximum), rf)
+        }
+        (Circumstance::EndAltitude, Body::Sun) => {
+            return eclipse_altitude(e, local()?.map(|l| l.end), rf)
+        }
+        (Circumstance::BeginAltitude, Body::Moon) => {
+            return eclipse_altitude(e, Some(e.contacts().0), rf)
+        }
+        (Circumstance::MaximumAltitude, Body::Moon) => {
+            return eclipse_altitude(e, Some(e.greatest), rf)
+        }
+        (Circumstance::EndAltitude, Body::Moon) => {
+            return eclipse_altitude(e, Some(e.contacts().1), rf)
+        }
+    })
+}
+
+/// Apparent altitude of the eclipsed sun or moon at a contact, if the eclipse is seen
+fn eclipse_altitude(
+    e: &eclipse::Eclipse,
+    contact: Option<time::Date>,
+    rf: &RefFrame,
+) -> Result<Value, Error> {
+    let obj = match e.body {
+        eclipse::Body::Sun => CelObj::Sun,
+        eclipse::Body::Moon => CelObj::Moon,
+    };
+    Ok(match contact {
+        Some(date) => {
+            let alt = event::altitude(&obj, &RefFrame { date, ..*rf })?;
+            Value::Ang(
+                rf.refraction.apparent(time::Angle::from_degrees(alt)),
+                AngView::Latitude,
             )
-            .unwrap_or_else(|e| panic!("Error on property {prop}: {e}"))
+        }
+        None => Value::Text("none".to_string()),
+    })
+}
+
+/// The columns of the table given by [`eclipses`]
+///
+/// Contact times of solar eclipses are only given with an observer, since they depend on where it is.
+pub fn eclipse_columns(object: &CelObj, rf: &RefFrame) -> Vec<Property> {
+    use eclipse::Circumstance::*;
+    let columns = match (object, rf.latlong) {
+        (CelObj::Sun, None) => vec![Kind, Magnitude, Gamma],
+        (CelObj::Sun, Some(_)) => vec![
+            Kind,
+            Magnitude,
+            Gamma,
+            Begin,
+            Maximum,
+            End,
+            Obscuration,
+            BeginAltitude,
+            MaximumAltitude,
+            EndAltitude,
+        ],
+        (_, None) => vec![
+            Kind,
+            Magnitude,
+            Gamma,
+            Begin,
+            TotalityBegin,
+            TotalityEnd,
+            End,
+        ],
+        (_, Some(_)) => vec![
+            Kind,
+            Magnitude,
+            Gamma,
+            Begin,
+            TotalityBegin,
+            TotalityEnd,
+            End,
+            BeginAltitude,
+            MaximumAltitude,
+            EndAltitude,
+        ],
+    };
+    columns.into_iter().map(Property::Eclipse).collect()
+}
+
+/// Lists every eclipse of the sun or moon until `end`
+///
+/// Each row is the date of greatest eclipse, followed by values for [`eclipse_columns`].
+pub fn eclipses(
+    object: &CelObj,
+    rf: &RefFrame,
+    end: time::Date,
+) -> Result<Vec<(time::Date, Vec<Value>)>, Error> {
+    let body = match (object, rf.from) {
+        (CelObj::Sun, None) => eclipse::Body::Sun,
+        (CelObj::Moon, None) => eclipse::Body::Moon,
+        _ => {
+            return Err(Error::Unsupported(
+                "Eclipses".to_string(),
+                "anything but the sun or moon",
+            ))
+        }
+    };
+    let columns = eclipse_columns(object, rf);
+    eclipse::between(body, rf.date, end)
+        .iter()
+        .map(|e| {
+            let row = columns
+                .iter()
+                .map(|p| match p {
+                    Property::Eclipse(c) => eclipse_value(e, *c, rf),
+                    _ => unreachable!(),
+                })
+                .collect::<Result<_, _>>()?;
+            Ok((e.greatest, row))
+        })
+        .collect()
+}
+
+/// How far ahead occultations are searched for when given as properties, in days
+const OCCULTATION_WINDOW: f64 = 366.0;
+
+/// A detail of an occultation, or `none` for everything if there isn't one
+fn occultation_value(
+    o: Option<&occultation::Occultation>,
+    c: occultation::Circumstance,
+    rf: &RefFrame,
+) -> Result<Value, Error> {
+    use occultation::Circumstance;
+    let none = || Value::Text("none".to_string());
+    let contact = match c {
+        Circumstance::Disappearance
+        | Circumstance::DisappearanceAngle
+        | Circumstance::DisappearanceLimb => {
+            rf.latlong.ok_or(Error::MissingLocation)?;
+            o.and_then(|o| o.disappearance)
+        }
+        Circumstance::Reappearance
+        | Circumstance::ReappearanceAngle
+        | Circumstance::ReappearanceLimb => {
+            rf.latlong.ok_or(Error::MissingLocation)?;
+            o.and_then(|o| o.reappearance)
+        }
+        _ => None,
+    };
+    Ok(match c {
+        Circumstance::Star => o.map_or_else(none, |o| Value::Text(o.star.clone())),
+        Circumstance::Conjunction => Value::Event(o.map(|o| o.conjunction)),
+        Circumstance::Separation => o.map_or_else(none, |o| {
+            Value::Ang(time::Angle::from_degrees(o.separation), AngView::Latitude)
+        }),
+        Circumstance::Disappearance | Circumstance::Reappearance => {
+            Value::Event(contact.map(|x| x.date))
+        }
+        Circumstance::DisappearanceAngle | Circumstance::ReappearanceAngle => {
+            contact.map_or_else(none, |x| Value::Ang(x.angle, AngView::Angle))
+        }
+        Circumstance::DisappearanceLimb | Circumstance::ReappearanceLimb => {
+            contact.map_or_else(none, |x| Value::Text(x.limb.to_string()))
+        }
+    })
+}
+
+/// The columns of the table given by [`occultations`]
+///
+/// Disappearance and reappearance are only given with an observer, since they depend on where it is.
+pub fn occultation_columns(stars: &occultation::Stars, rf: &RefFrame) -> Vec<Property> {
+    use occultation::Circumstance::*;
+    let columns = match rf.latlong {
+        None => vec![Star, Separation],
+        Some(_) => vec![
+            Star,
+            Disappearance,
+            DisappearanceAngle,
+            DisappearanceLimb,
+            Reappearance,
+            ReappearanceAngle,
+            ReappearanceLimb,
+        ],
+    };
+    columns
+        .into_iter()
+        .map(|c| Property::Occultation(c, stars.clone()))
+        .collect()
+}
+
+/// Lists every occultation of the given stars by the moon or a planet until `end`
+///
+/// Each row is the date of geocentric conjunction, followed by values for [`occultation_columns`].
+pub fn occultations(
+    object: &CelObj,
+    stars: &occultation::Stars,
+    rf: &RefFrame,
+    end: time::Date,
+) -> Result<Vec<(time::Date, Vec<Value>)>, Error> {
+    let columns = occultation_columns(stars, rf);
+    occultation::search(object, stars, rf, end)?
+        .iter()
+        .map(|o| {
+            let row = columns
+                .iter()
+                .map(|p| match p {
+                    Property::Occultation(c, _) => occultation_value(Some(o), *c, rf),
+                    _ => unreachable!(),
+                })
+                .collect::<Result<_, _>>()?;
+            Ok((o.conjunction, row))
+        })
+        .collect()
+}
+
+/// The columns of the table given by [`aspects`]
+pub const ASPECT_COLUMNS: [Property; 2] = [Property::Aspect, Property::Elongation];
+
+/// Lists every aspect of a planet until `end`, with its elongation then
+pub fn aspects(
+    object: &CelObj,
+    rf: &RefFrame,
+    end: time::Date,
+) -> Result<Vec<(time::Date, Vec<Value>)>, Error> {
+    event::aspects(object, rf, end)?
+        .into_iter()
+        .map(|(d, a)| {
+            let at = RefFrame { date: d, ..*rf };
+            Ok((
+                d,
+                vec![
+                    Value::Text(a.to_string()),
+                    property_of(object, Property::Elongation, &at)?,
+                ],
+            ))
+        })
+        .collect()
+}
+
+/// Lists every time the object is closest to `other` until `end`, with the angle between them then
+pub fn conjunctions(
+    object: &CelObj,
+    other: &CelObj,
+    rf: &RefFrame,
+    end: time::Date,
+) -> Result<Vec<(time::Date, Vec<Value>)>, Error> {
+    event::conjunctions(object, other, rf, end)?
+        .into_iter()
+        .map(|d| {
+            let at = RefFrame { date: d, ..*rf };
+            Ok((
+                d,
+                vec![property_of(object, Property::AngBet(other.clone()), &at)?],
+            ))
         })
+        .collect()
+}
+
+/// The columns of the table given by [`seasons`]
+pub fn season_columns(terms: event::Terms) -> Vec<Property> {
+    vec![Property::Term(terms)]
+}
+
+/// Lists the times in a year (in UT) the sun reaches each of the terms
+pub fn seasons(
+    object: &CelObj,
+    rf: &RefFrame,
+    year: i64,
+    terms: event::Terms,
+) -> Result<Vec<(time::Date, Vec<Value>)>, Error> {
+    if *object != CelObj::Sun || rf.from.is_some() {
+        return Err(Error::Unsupported(
+            "Seasons".to_string(),
+            "anything but the sun",
+        ));
+    }
+    let new_year = |y| time::Date::from_calendar(y, 1, 1, time::Angle::from_degrees(0.0));
+    Ok(event::terms(terms, new_year(year), new_year(year + 1))?
+        .into_iter()
+        .map(|(d, t)| (d, vec![Value::Text(t.to_string())]))
         .collect())
 }
+
+/// The columns of the table given by [`passes`]
+pub const PASS_COLUMNS: [Property; 5] = [
+    Property::Rise,
+    Property::Transit,
+    Property::Set,
+    Property::TransitAlt,
+    Property::Sunlit,
+];
+
+/// Lists every pass of a satellite over the observer until `end`
+///
+/// Each row is the date of rising, followed by values for [`PASS_COLUMNS`], with the
+/// altitude and illumination taken at culmination.
+pub fn passes(
+    object: &CelObj,
+    rf: &RefFrame,
+    end: time::Date,
+) -> Result<Vec<(time::Date, Vec<Value>)>, Error> {
+    let CelObj::Satellite(s) = object else {
+        return Err(Error::Unsupported(
+            "Passes".to_string(),
+            "anything but a satellite",
+        ));
+    };
+    event::passes(object, rf, end)?
+        .into_iter()
+        .map(|p| {
+            Ok((
+                p.rise,
+                vec![
+                    Value::RsTime(Some(p.rise)),
+                    Value::RsTime(Some(p.culmination)),
+                    Value::RsTime(Some(p.set)),
+                    Value::Ang(
+                        rf.refraction.apparent(time::Angle::from_degrees(p.max_alt)),
+                        AngView::Latitude,
+                    ),
+                    Value::Bool(s.sunlit(p.culmination)?),
+                ],
+            ))
+        })
+        .collect()
+}
+
+/// Columns a survey of the sky can be sorted by
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SortBy {
+    Name,
+    /// Highest first
+    Altitude,
+    Azimuth,
+    /// Brightest first
+    Magnitude,
+    Rise,
+    Transit,
+    Set,
+}
+impl clap::ValueEnum for SortBy {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[
+            SortBy::Name,
+            SortBy::Altitude,
+            SortBy::Azimuth,
+            SortBy::Magnitude,
+            SortBy::Rise,
+            SortBy::Transit,
+            SortBy::Set,
+        ]
+    }
+
+    fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
+        Some(clap::builder::PossibleValue::new(match self {
+            SortBy::Name => "name",
+            SortBy::Altitude => "alt",
+            SortBy::Azimuth => "az",
+            SortBy::Magnitude => "mag",
+            SortBy::Rise => "rise",
+            SortBy::Transit => "transit",
+            SortBy::Set => "set",
+        }))
+    }
+}
+
+/// The columns of the table given by [`survey`]
+pub const SURVEY_COLUMNS: [Property; 5] = [
+    Property::Horizontal,
+    Property::Magnitude,
+    Property::Rise,
+    Property::Transit,
+    Property::Set,
+];
+
+/// Lists every object in the catalog that's above `min_alt` degrees of apparent altitude, and
+/// brighter than `mag_limit` if there is one, sorted by a column
+///
+/// Objects in the catalog under several names are listed once, by the shortest name, which is
+/// usually the catalog number. Objects without a magnitude are left out if there's a limit, and
+/// sorted last by magnitude, as are objects that don't rise, transit, or set by those. Objects
+/// whose position can't be found are left out, and times that can't be found are left empty.
+pub fn survey(
+    cat: &catalog::Catalog,
+    rf: &RefFrame,
+    min_alt: f64,
+    mag_limit: Option<f64>,
+    sort: SortBy,
+) -> Result<Vec<(String, Vec<Value>)>, Error> {
+    let (lat, long, _) = rf.latlong.ok_or(Error::MissingLocation)?;
+    let mut names: Vec<&String> = cat.keys().collect();
+    names.sort_by(|a, b| a.len().cmp(&b.len()).then(a.cmp(b)));
+
+    let mut seen: Vec<&CelObj> = vec![];
+    let mut found = vec![];
+    for name in names {
+        let obj = &cat[name];
+        // Not in the sky, or not seen from it
+        if *obj == CelObj::Site || *obj == CelObj::Planet(sol::EARTH) || seen.contains(&obj) {
+            continue;
+        }
+        seen.push(obj);
+
+        // Anything that can't be placed, like a satellite that's decayed, isn't up
+        let Ok(horizontal @ Value::Crd(c, _)) = property_of(obj, Property::Horizontal, rf) else {
+            continue;
+        };
+        let (azi, alt) = sidereal::horizon(c, rf.date, lat, long);
+        let alt = rf.refraction.apparent(alt).to_latitude().degrees();
+        if alt < min_alt {
+            continue;
+        }
+        let mag = match property_of(obj, Property::Magnitude, rf) {
+            Ok(Value::Num(m)) => Some(m),
+            _ => None,
+        };
+        if mag_limit.is_some_and(|l| !mag.is_some_and(|m| m <= l)) {
+            continue;
+        }
+        // The outer option is if the time could be found, the inner one if there is one
+        let at = |p| match property_of(obj, p, rf) {
+            Ok(Value::RsTime(d)) => Some(d),
+            _ => None,
+        };
+        let (rise, transit, set) = (at(Property::Rise), at(Property::Transit), at(Property::Set));
+
+        let key = match sort {
+            SortBy::Name => None,
+            SortBy::Altitude => Some(-alt),
+            SortBy::Azimuth => Some(azi.degrees()),
+            SortBy::Magnitude => mag,
+            SortBy::Rise => rise.flatten().map(|d| d.julian()),
+            SortBy::Transit => transit.flatten().map(|d| d.julian()),
+            SortBy::Set => set.flatten().map(|d| d.julian()),
+        };
+        let time =
+            |t: Option<Option<time::Date>>| t.map_or(Value::Text(String::new()), Value::RsTime);
+        let row = vec![
+            horizontal,
+            mag.map_or(Value::Text("none".to_string()), Value::Num),
+            time(rise),
+            time(transit),
+            time(set),
+        ];
+        found.push((name.clone(), row, key));
+    }
+
+    found.sort_by(|a, b| {
+        let by_key = match (a.2, b.2) {
+            (Some(x), Some(y)) => x.total_cmp(&y),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        };
+        by_key.then(a.0.cmp(&b.0))
+    });
+    Ok(found.into_iter().map(|(n, r, _)| (n, r)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A geostationary satellite, which is too far out for SGP4
+    const DEEP_SPACE: &str = "GEO TEST
+1 28884U 05041A   25014.50000000 -.00000150  00000-0  00000+0 0  9990
+2 28884   0.0200  90.0000 0002000 270.0000  90.0000  1.00270000 70000
+";
+
+    #[test]
+    fn survey_skips_what_it_cant_place() {
+        let path = std::env::temp_dir().join("deskephem-survey-deep-space.tle");
+        std::fs::write(&path, DEEP_SPACE).unwrap();
+        let mut cat = catalog::read();
+        catalog::merge(&mut cat, catalog::read_tle(path.to_str().unwrap()).unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        let deg = time::Angle::from_degrees;
+        let rf = RefFrame {
+            latlong: Some((deg(40.0), deg(-95.0), 0.0)),
+            date: time::Date::from_calendar(2025, 1, 15, time::Angle::from_turns(0.125)),
+            refraction: Refraction::Standard,
+            from: None,
+        };
+        let rows = survey(&cat, &rf, 0.0, None, SortBy::Altitude).unwrap();
+        let names: Vec<&str> = rows.iter().map(|(n, _)| n.as_str()).collect();
+        assert!(!names.contains(&"geo test"));
+        // Jupiter, Capella and Sirius are up that evening in the middle of the US
+        assert!(["jupiter", "capella", "sirius"]
+            .iter()
+            .all(|x| names.contains(x)));
+    }
+
+    /// Midnight UT at a site, with standard refraction
+    fn site(lat: f64, long: f64, (y, m, d): (i64, u8, u8)) -> RefFrame {
+        let deg = time::Angle::from_degrees;
+        RefFrame {
+            latlong: Some((deg(lat), deg(long), 0.0)),
+            date: time::Date::from_calendar(y, m, d, time::Angle::from_turns(0.0)),
+            refraction: Refraction::Standard,
+            from: None,
+        }
+    }
+
+    /// The time of an event the sun has
+    fn sun_event(q: &Property, rf: &RefFrame) -> Option<time::Date> {
+        let Ok(Value::RsTime(t)) = property_of(&CelObj::Sun, q.clone(), rf) else {
+            panic!("No time for {q}");
+        };
+        t
+    }
+
+    #[test]
+    fn london_solstice_transit() {
+        let rf = site(51.5074, -0.1278, (2025, 6, 21));
+        // The sun is 23°26′ north of the equator, so 90° - 51°30′ + 23°26′ high, and a little more with refraction
+        let Ok(Value::Ang(alt, _)) = property_of(&CelObj::Sun, Property::TransitAlt, &rf) else {
+            panic!("No transit altitude");
+        };
+        assert!((alt.to_latitude().degrees() - 61.938).abs() < 1.0 / 60.0);
+        // Local noon in London is a couple of minutes after 12:00 UT near the solstice
+        let hours = sun_event(&Property::Transit, &rf)
+            .unwrap()
+            .calendar()
+            .3
+            .decimal();
+        assert!((hours - (12.0 + 2.0 / 60.0)).abs() < 1.0 / 60.0, "{hours}");
+    }
+
+    #[test]
+    fn london_solstice_twilight() {
+        // The sun only gets 15° below the horizon, so it's never astronomical night
+        let rf = site(51.5074, -0.1278, (2025, 6, 21));
+        assert!(sun_event(&Property::TwilightEnd(Twilight::Civil), &rf).is_some());
+        assert!(sun_event(&Property::TwilightEnd(Twilight::Nautical), &rf).is_some());
+        assert_eq!(
+            sun_event(&Property::TwilightEnd(Twilight::Astronomical), &rf),
+            None
+        );
+        assert_eq!(
+            sun_event(&Property::TwilightBegin(Twilight::Astronomical), &rf),
+            None
+        );
+    }
+
+    #[test]
+    fn equator_equinox_twilight() {
+        // The sun goes straight down at 15° an hour, so civil twilight lasts 21 minutes
+        // after sunset, and nautical and astronomical twilight 24 minutes each
+        let rf = site(0.0, 0.0, (2025, 3, 20));
+        let minutes = |a: &Property, b: &Property| {
+            let at = |q| sun_event(q, &rf).unwrap().julian();
+            (at(b) - at(a)) * 1440.0
+        };
+        let ends = [
+            Property::Set,
+            Property::TwilightEnd(Twilight::Civil),
+            Property::TwilightEnd(Twilight::Nautical),
+            Property::TwilightEnd(Twilight::Astronomical),
+        ];
+        for (w, length) in ends.windows(2).zip([21.0, 24.0, 24.0]) {
+            let found = minutes(&w[0], &w[1]);
+            assert!((found - length).abs() < 0.5, "{} {found}", w[1]);
+        }
+        // Dawn mirrors dusk
+        let dawn = minutes(
+            &Property::TwilightBegin(Twilight::Civil),
+            &Property::TwilightBegin(Twilight::Nautical),
+        );
+        assert!((dawn + 24.0).abs() < 0.5, "{dawn}");
+    }
+
+    #[test]
+    fn meeus_mars_parallax() {
+        // Example 40.a, Mars from Palomar
+        let mut rf = site(
+            33.0 + 21.0 / 60.0 + 22.0 / 3600.0,
+            -(116.0 + 51.0 / 60.0 + 47.0 / 3600.0),
+            (2003, 8, 28),
+        );
+        rf.date = time::Date::from_calendar(2003, 8, 28, time::Angle::from_clock(3, 17, 0.0));
+        rf.latlong = rf.latlong.map(|(lat, long, _)| (lat, long, 1706.0));
+        let geo = coord::Coord::from_equatorial(
+            time::Angle::from_clock(22, 38, 7.25),
+            time::Angle::from_degrees(-(15.0 + 46.0 / 60.0 + 15.9 / 3600.0)),
+        );
+        let (ra, de) = topocentric(geo, 0.37276, &rf).equatorial();
+        // 1.29 seconds east to 22h38m08.54s, and down to -15°46′30.0″
+        let dra = (ra - geo.equatorial().0).to_latitude().degrees() * 240.0;
+        assert!((dra - 1.29).abs() < 0.02, "{dra}");
+        let de = de.to_latitude().degrees() * 3600.0;
+        assert!(
+            (de + (15.0 * 3600.0 + 46.0 * 60.0 + 30.0)).abs() < 0.2,
+            "{de}"
+        );
+    }
+
+    #[test]
+    fn moon_parallax_on_the_horizon() {
+        // 368409.7 km away (Meeus, Example 47.a), so the horizontal parallax is
+        // asin(6378.14 / 368409.7), or 0°59′31.0″
+        let d = time::Date::from_calendar(1992, 4, 12, time::Angle::from_turns(0.0));
+        let (long, lat, dist) = crate::lunar::moon(d);
+        let deg = time::Angle::from_degrees;
+        let geo = coord::Coord::from_ecliptic(deg(long), deg(lat), d);
+        // An observer on the equator with the moon six hours west, so it's setting
+        let long = geo.equatorial().0 + deg(90.0) - sidereal::gmst(d);
+        let rf = RefFrame {
+            latlong: Some((deg(0.0), long, 0.0)),
+            ..geocentric(d)
+        };
+        let shift = topocentric(geo, dist / 149_597_870.7, &rf).dist(geo);
+        let expected = 59.0 * 60.0 + 31.0;
+        assert!(
+            (shift.degrees() * 3600.0 - expected).abs() < 1.0,
+            "{}",
+            shift.degrees() * 3600.0
+        );
+    }
+
+    #[test]
+    fn airmass_formulas() {
+        // Kasten and Young give 37.92 at the horizon, and Pickering 38.75; both are 2 at 30°
+        assert!((Airmass::KastenYoung.at(0.0, 0.0) - 37.92).abs() < 0.01);
+        assert!((Airmass::Pickering.at(0.0, 0.0) - 38.75).abs() < 0.01);
+        for model in [Airmass::KastenYoung, Airmass::Pickering, Airmass::Secant] {
+            assert!((model.at(90.0, 90.0) - 1.0).abs() < 0.001);
+            assert!((model.at(30.0, 30.0) - 2.0).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn parallactic_angle() {
+        // A star on the equator six hours west has a parallactic angle of 90° less the latitude
+        let deg = time::Angle::from_degrees;
+        let star = CelObj::Star(catalog::Star {
+            loc_j2k: coord::Coord::from_equatorial(deg(0.0), deg(0.0)),
+            mag: 0.0,
+            pi: deg(0.0),
+            pm_ra: deg(0.0),
+            pm_dec: deg(0.0),
+            rv: 0.0,
+        });
+        // The longitudes where it's six hours west and on the meridian at J2000
+        let west = deg(90.0) - sidereal::gmst(time::J2000);
+        let meridian = deg(0.0) - sidereal::gmst(time::J2000);
+        let mut rf = RefFrame {
+            latlong: Some((deg(40.0), west, 0.0)),
+            ..geocentric(time::J2000)
+        };
+        let Ok(Value::Num(ha)) = property_of(&star, Property::HourAngle, &rf) else {
+            panic!("No hour angle");
+        };
+        assert!((ha - 6.0).abs() < 1e-6);
+        let Ok(Value::Num(q)) = property_of(&star, Property::Parallactic, &rf) else {
+            panic!("No parallactic angle");
+        };
+        assert!((q - 50.0).abs() < 1e-6);
+        // On the meridian, it's upright
+        rf.latlong = Some((deg(40.0), meridian, 0.0));
+        let Ok(Value::Num(q)) = property_of(&star, Property::Parallactic, &rf) else {
+            panic!("No parallactic angle");
+        };
+        assert!(q.abs() < 1e-6);
+    }
+
+    fn geocentric(date: time::Date) -> RefFrame {
+        RefFrame {
+            latlong: None,
+            date,
+            refraction: Refraction::Standard,
+            from: None,
+        }
+    }
+
+    #[test]
+    fn moon_xyz_points_at_the_moon() {
+        let rf = geocentric(time::Date::from_calendar(
+            2025,
+            4,
+            1,
+            time::Angle::from_turns(0.0),
+        ));
+        let xyz = Property::Rectangular(Center::Earth, Plane::Equatorial, DistUnit::Au);
+        let Ok(Value::Xyz((x, y, z), _)) = property_of(&CelObj::Moon, xyz, &rf) else {
+            panic!("No rectangular coordinates for the moon");
+        };
+        let Ok(Value::Crd(equ, _)) = property_of(&CelObj::Moon, Property::Equatorial, &rf) else {
+            panic!("No position for the moon");
+        };
+        let Ok(Value::Dist(dist)) = property_of(&CelObj::Moon, Property::Distance, &rf) else {
+            panic!("No distance for the moon");
+        };
+        let direction = coord::Coord::from_cartesian(x, y, z).precess(time::J2000, rf.date);
+        assert!(direction.dist(equ).degrees() < 0.01);
+        assert!(((x * x + y * y + z * z).sqrt() - dist).abs() < 1e-9);
+        // 02h57m +21°01′ that day
+        let (ra, de) = equ.equatorial();
+        assert!((ra.degrees() - 44.3).abs() < 0.1 && (de.degrees() - 21.0).abs() < 0.1);
+    }
+
+    /// Equatorial position on J2000 axes and distance, as a vector in AU
+    fn vector(obj: &CelObj, rf: &RefFrame) -> (f64, f64, f64) {
+        let Ok(Value::Crd(c, _)) = property_of(obj, Property::Equatorial, rf) else {
+            panic!("No position");
+        };
+        let Ok(Value::Dist(dist)) = property_of(obj, Property::Distance, rf) else {
+            panic!("No distance");
+        };
+        let (ra, de) = constellation::precess(c, equinox(obj, rf), time::J2000).equatorial();
+        (
+            dist * de.cos() * ra.cos(),
+            dist * de.cos() * ra.sin(),
+            dist * de.sin(),
+        )
+    }
+
+    #[test]
+    fn moon_from_mars() {
+        let rf = geocentric(time::Date::from_calendar(
+            2025,
+            4,
+            1,
+            time::Angle::from_turns(0.0),
+        ));
+        let mars = RefFrame {
+            from: Some(&sol::MARS),
+            ..rf
+        };
+        // The earth seen from mars, and the moon seen from the earth
+        let (e, m) = (
+            vector(&CelObj::Planet(sol::EARTH), &mars),
+            vector(&CelObj::Moon, &rf),
+        );
+        let (x, y, z) = vector(&CelObj::Moon, &mars);
+        let sum = (e.0 + m.0, e.1 + m.1, e.2 + m.2);
+        let miss = ((x - sum.0).powi(2) + (y - sum.1).powi(2) + (z - sum.2).powi(2)).sqrt();
+        // Well under the 0.0026 AU the moon is from the earth
+        assert!(miss < 1e-6);
+    }
+
+    #[test]
+    fn meeus_sun_xyz() {
+        // Example 26.a, the geometric position of the sun on 1992 October 13.0, on J2000 axes
+        let rf = geocentric(time::Date::from_calendar(
+            1992,
+            10,
+            13,
+            time::Angle::from_turns(0.0),
+        ));
+        let xyz = Property::Rectangular(Center::Earth, Plane::Equatorial, DistUnit::Au);
+        let Ok(Value::Xyz((x, y, z), _)) = property_of(&CelObj::Sun, xyz, &rf) else {
+            panic!("No rectangular coordinates for the sun");
+        };
+        let (ex, ey, ez) = (-0.93739590, -0.31316793, -0.13577924);
+        assert!(((x - ex).powi(2) + (y - ey).powi(2) + (z - ez).powi(2)).sqrt() < 1e-4);
+        // On the J2000 ecliptic, the sun is off the plane by the few arcseconds it has moved since
+        let xyz = Property::Rectangular(Center::Earth, Plane::Ecliptic, DistUnit::Au);
+        let Ok(Value::Xyz((lx, ly, lz), _)) = property_of(&CelObj::Sun, xyz, &rf) else {
+            panic!("No ecliptic rectangular coordinates for the sun");
+        };
+        assert!((lx * lx + ly * ly + lz * lz - (x * x + y * y + z * z)).abs() < 1e-12);
+        assert!(lz.abs() < 1e-5);
+    }
+
+    /// An angle a body has, between -180° and 180°
+    fn angle(obj: &CelObj, q: Property, rf: &RefFrame) -> f64 {
+        let Ok(Value::Ang(a, _)) = property_of(obj, q.clone(), rf) else {
+            panic!("No angle for {q}");
+        };
+        a.to_latitude().degrees()
+    }
+
+    #[test]
+    fn meeus_venus_phase() {
+        // Example 41.a: Δ = 0.910845, r = 0.724604 and R = 0.983824 AU, so the phase angle is 72.96°,
+        // 64.7% of the disk is lit, and Venus is 44.77° east of the sun
+        let rf = geocentric(time::Date::from_calendar(
+            1992,
+            12,
+            20,
+            time::Angle::from_turns(0.0),
+        ));
+        let venus = CelObj::Planet(sol::VENUS);
+        let i = angle(&venus, Property::PhaseAngle, &rf);
+        assert!((i - 72.96).abs() < 0.05, "{i}");
+        let Ok(Value::Phase(p, _)) = property_of(&venus, Property::IllumFrac, &rf) else {
+            panic!("No phase");
+        };
+        let k = 0.5 * (1.0 - p.cos());
+        assert!((k - 0.647).abs() < 0.001, "{k}");
+        let elong = angle(&venus, Property::Elongation, &rf);
+        assert!((elong - 44.77).abs() < 0.05, "{elong}");
+    }
+
+    #[test]
+    fn meeus_moon_bright_limb() {
+        // Example 48.a gives 285.0°; the moon here is a little less exact than Meeus's
+        let rf = geocentric(time::Date::from_calendar(
+            1992,
+            4,
+            12,
+            time::Angle::from_turns(0.0),
+        ));
+        let Ok(Value::Ang(chi, _)) = property_of(&CelObj::Moon, Property::BrightLimb, &rf) else {
+            panic!("No bright limb");
+        };
+        assert!((chi.degrees() - 285.0).abs() < 0.5, "{}", chi.degrees());
+    }
+
+    #[test]
+    fn no_xyz_of_the_observers_planet() {
+        let rf = geocentric(time::Date::from_calendar(
+            2025,
+            4,
+            1,
+            time::Angle::from_turns(0.0),
+        ));
+        let xyz = Property::Rectangular(Center::Earth, Plane::Equatorial, DistUnit::Au);
+        assert!(matches!(
+            property_of(&CelObj::Planet(sol::EARTH), xyz, &rf),
+            Err(Error::Unsupported(..))
+        ));
+    }
+}
@@ -0,0 +1,351 @@
+use pracstro::{coord, sol, time};
+use std::f64::consts::TAU;
+
+// WGS-72, which two-line elements are made with
+const EARTH_RADIUS: f64 = 6378.135; // km
+const XKE: f64 = 0.074_366_916_1; // sqrt(GM) in earth radii^1.5 per minute
+const J2: f64 = 0.001_082_616;
+const J3: f64 = -0.000_002_538_81;
+const J4: f64 = -0.000_001_655_97;
+const KM_PER_AU: f64 = 149_597_870.7;
+
+/// An earth satellite, from its two-line elements
+///
+/// Positions come from the SGP4 model, for near earth orbits (periods under 225 minutes).
+/// Deep space orbits need the SDP4 extensions, which aren't implemented.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Satellite {
+    pub epoch: time::Date,
+    /// Inclination
+    pub incl: time::Angle,
+    /// Right ascension of the ascending node
+    pub node: time::Angle,
+    pub ecc: f64,
+    /// Argument of perigee
+    pub argp: time::Angle,
+    pub mean_anomaly: time::Angle,
+    /// Mean motion, revolutions per day
+    pub mean_motion: f64,
+    /// Drag term, per earth radius
+    pub bstar: f64,
+}
+
+/// Why a position couldn't be found
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Sgp4Error {
+    DeepSpace,
+    Decayed,
+}
+
+impl Satellite {
+    /// Position in km from the center of the earth, in the TEME frame (true equator, mean equinox of date)
+    ///
+    /// Vallado, Crawford, Hujsak, Kelso, "Revisiting Spacetrack Report #3" (2006)
+    pub fn position(&self, d: time::Date) -> Result<(f64, f64, f64), Sgp4Error> {
+        let (ecco, inclo, argpo, mo, nodeo, bstar) = (
+            self.ecc,
+            self.incl.radians(),
+            self.argp.radians(),
+            self.mean_anomaly.radians(),
+            self.node.radians(),
+            self.bstar,
+        );
+        let x2o3 = 2.0 / 3.0;
+        let j3oj2 = J3 / J2;
+
+        // Recover the original mean motion and semimajor axis from the elements
+        let no_kozai = self.mean_motion * TAU / 1440.0;
+        let omeosq = 1.0 - ecco * ecco;
+        let rteosq = omeosq.sqrt();
+        let (sinio, cosio) = inclo.sin_cos();
+        let cosio2 = cosio * cosio;
+        let ak = (XKE / no_kozai).powf(x2o3);
+        let d1 = 0.75 * J2 * (3.0 * cosio2 - 1.0) / (rteosq * omeosq);
+        let del = d1 / (ak * ak);
+        let adel = ak * (1.0 - del * del - del * (1.0 / 3.0 + 134.0 * del * del / 81.0));
+        let no = no_kozai / (1.0 + d1 / (adel * adel));
+        if TAU / no >= 225.0 {
+            return Err(Sgp4Error::DeepSpace);
+        }
+        let ao = (XKE / no).powf(x2o3);
+        let po = ao * omeosq;
+        let con42 = 1.0 - 5.0 * cosio2;
+        let con41 = -con42 - 2.0 * cosio2;
+        let posq = po * po;
+        let rp = ao * (1.0 - ecco);
+
+        // Atmospheric drag coefficients
+        let ss = 78.0 / EARTH_RADIUS + 1.0;
+        let simple = rp < 220.0 / EARTH_RADIUS + 1.0;
+        let perige = (rp - 1.0) * EARTH_RADIUS;
+        let (sfour, qzms24) = if perige < 156.0 {
+            let s = if perige < 98.0 { 20.0 } else { perige - 78.0 };
+            (s / EARTH_RADIUS + 1.0, ((120.0 - s) / EARTH_RADIUS).powi(4))
+        } else {
+            (ss, ((120.0 - 78.0) / EARTH_RADIUS).powi(4))
+        };
+        let pinvsq = 1.0 / posq;
+        let tsi = 1.0 / (ao - sfour);
+        let eta = ao * ecco * tsi;
+        let etasq = eta * eta;
+        let eeta = ecco * eta;
+        let psisq = (1.0 - etasq).abs();
+        let coef = qzms24 * tsi.powi(4);
+        let coef1 = coef / psisq.powf(3.5);
+        let cc2 = coef1
+            * no
+            * (ao * (1.0 + 1.5 * etasq + eeta * (4.0 + etasq))
+                + 0.375 * J2 * tsi / psisq * con41 * (8.0 + 3.0 * etasq * (8.0 + etasq)));
+        let cc1 = bstar * cc2;
+        let cc3 = if ecco > 1e-4 {
+            -2.0 * coef * tsi * j3oj2 * no * sinio / ecco
+        } else {
+            0.0
+        };
+        let x1mth2 = 1.0 - cosio2;
+        let cc4 = 2.0
+            * no
+            * coef1
+            * ao
+            * omeosq
+            * (eta * (2.0 + 0.5 * etasq) + ecco * (0.5 + 2.0 * etasq)
+                - J2 * tsi / (ao * psisq)
+                    * (-3.0 * con41 * (1.0 - 2.0 * eeta + etasq * (1.5 - 0.5 * eeta))
+                        + 0.75
+                            * x1mth2
+                            * (2.0 * etasq - eeta * (1.0 + etasq))
+                            * (2.0 * argpo).cos()));
+        let cc5 = 2.0 * coef1 * ao * omeosq * (1.0 + 2.75 * (etasq + eeta) + eeta * etasq);
+
+        // Secular rates from the earth's oblateness
+        let cosio4 = cosio2 * cosio2;
+        let temp1 = 1.5 * J2 * pinvsq * no;
+        let temp2 = 0.5 * temp1 * J2 * pinvsq;
+        let temp3 = -0.46875 * J4 * pinvsq * pinvsq * no;
+        let mdot = no
+            + 0.5 * temp1 * rteosq * con41
+            + 0.0625 * temp2 * rteosq * (13.0 - 78.0 * cosio2 + 137.0 * cosio4);
+        let argpdot = -0.5 * temp1 * con42
+            + 0.0625 * temp2 * (7.0 - 114.0 * cosio2 + 395.0 * cosio4)
+            + temp3 * (3.0 - 36.0 * cosio2 + 49.0 * cosio4);
+        let xhdot1 = -temp1 * cosio;
+        let nodedot = xhdot1
+            + (0.5 * temp2 * (4.0 - 19.0 * cosio2) + 2.0 * temp3 * (3.0 - 7.0 * cosio2)) * cosio;
+        let omgcof = bstar * cc3 * argpo.cos();
+        let xmcof = if ecco > 1e-4 {
+            -x2o3 * coef * bstar / eeta
+        } else {
+            0.0
+        };
+        let nodecf = 3.5 * omeosq * xhdot1 * cc1;
+        let t2cof = 1.5 * cc1;
+        let xlcof = -0.25 * j3oj2 * sinio * (3.0 + 5.0 * cosio) / (1.0 + cosio).max(1.5e-12);
+        let aycof = -0.5 * j3oj2 * sinio;
+        let delmo = (1.0 + eta * mo.cos()).powi(3);
+        let sinmao = mo.sin();
+        let x7thm1 = 7.0 * cosio2 - 1.0;
+
+        // Propagation
+        let t = (d.julian() - self.epoch.julian()) * 1440.0;
+        let xmdf = mo + mdot * t;
+        let argpdf = argpo + argpdot * t;
+        let nodedf = nodeo + nodedot * t;
+        let t2 = t * t;
+        let nodem = nodedf + nodecf * t2;
+        let (mut mm, mut argpm) = (xmdf, argpdf);
+        let mut tempa = 1.0 - cc1 * t;
+        let mut tempe = bstar * cc4 * t;
+        let mut templ = t2cof * t2;
+        if !simple {
+            let cc1sq = cc1 * cc1;
+            let d2 = 4.0 * ao * tsi * cc1sq;
+            let temp = d2 * tsi * cc1 / 3.0;
+            let d3 = (17.0 * ao + sfour) * temp;
+            let d4 = 0.5 * temp * ao * tsi * (221.0 * ao + 31.0 * sfour) * cc1;
+            let t3cof = d2 + 2.0 * cc1sq;
+            let t4cof = 0.25 * (3.0 * d3 + cc1 * (12.0 * d2 + 10.0 * cc1sq));
+            let t5cof = 0.2
+                * (3.0 * d4 + 12.0 * cc1 * d3 + 6.0 * d2 * d2 + 15.0 * cc1sq * (2.0 * d2 + cc1sq));
+
+            let delomg = omgcof * t;
+            let delm = xmcof * ((1.0 + eta * xmdf.cos()).powi(3) - delmo);
+            mm = xmdf + delomg + delm;
+            argpm = argpdf - delomg - delm;
+            let (t3, t4) = (t2 * t, t2 * t2);
+            tempa = tempa - d2 * t2 - d3 * t3 - d4 * t4;
+            tempe += bstar * cc5 * (mm.sin() - sinmao);
+            templ = templ + t3cof * t3 + t4 * (t4cof + t * t5cof);
+        }
+        let am = (XKE / no).powf(x2o3) * tempa * tempa;
+        let em = ecco - tempe;
+        if !(-0.001..1.0).contains(&em) || am < 0.95 {
+            return Err(Sgp4Error::Decayed);
+        }
+        let em = em.max(1e-6);
+        mm += no * templ;
+        let xlm = (mm + argpm + nodem).rem_euclid(TAU);
+        let nodem = nodem.rem_euclid(TAU);
+        let argpm = argpm.rem_euclid(TAU);
+        let mm = (xlm - argpm - nodem).rem_euclid(TAU);
+
+        // Long period periodics
+        let axnl = em * argpm.cos();
+        let temp = 1.0 / (am * (1.0 - em * em));
+        let aynl = em * argpm.sin() + temp * aycof;
+        let xl = mm + argpm + nodem + temp * xlcof * axnl;
+
+        // Kepler's equation
+        let u = (xl - nodem).rem_euclid(TAU);
+        let mut eo1 = u;
+        let (mut sineo1, mut coseo1) = eo1.sin_cos();
+        for _ in 0..10 {
+            (sineo1, coseo1) = eo1.sin_cos();
+            let tem5 =
+                (u - aynl * coseo1 + axnl * sineo1 - eo1) / (1.0 - coseo1 * axnl - sineo1 * aynl);
+            eo1 += tem5.clamp(-0.95, 0.95);
+            if tem5.abs() < 1e-12 {
+                break;
+            }
+        }
+
+        // Short period periodics
+        let ecose = axnl * coseo1 + aynl * sineo1;
+        let esine = axnl * sineo1 - aynl * coseo1;
+        let el2 = axnl * axnl + aynl * aynl;
+        let pl = am * (1.0 - el2);
+        if pl < 0.0 {
+            return Err(Sgp4Error::Decayed);
+        }
+        let rl = am * (1.0 - ecose);
+        let betal = (1.0 - el2).sqrt();
+        let temp = esine / (1.0 + betal);
+        let sinu = am / rl * (sineo1 - aynl - axnl * temp);
+        let cosu = am / rl * (coseo1 - axnl + aynl * temp);
+        let su = sinu.atan2(cosu);
+        let sin2u = 2.0 * cosu * sinu;
+        let cos2u = 1.0 - 2.0 * sinu * sinu;
+        let temp1 = 0.5 * J2 / pl;
+        let temp2 = temp1 / pl;
+
+        let mrt = rl * (1.0 - 1.5 * temp2 * betal * con41) + 0.5 * temp1 * x1mth2 * cos2u;
+        if mrt < 1.0 {
+            return Err(Sgp4Error::Decayed);
+        }
+        let su = su - 0.25 * temp2 * x7thm1 * sin2u;
+        let xnode = nodem + 1.5 * temp2 * cosio * sin2u;
+        let xinc = inclo + 1.5 * temp2 * cosio * sinio * cos2u;
+
+        let (sinsu, cossu) = su.sin_cos();
+        let (snod, cnod) = xnode.sin_cos();
+        let (sini, cosi) = xinc.sin_cos();
+        let ux = -snod * cosi * sinsu + cnod * cossu;
+        let uy = cnod * cosi * sinsu + snod * cossu;
+        let uz = sini * sinsu;
+        let r = mrt * EARTH_RADIUS;
+        Ok((r * ux, r * uy, r * uz))
+    }
+
+    /// Position relative to the observer in km, or the center of the earth if there isn't one
+    fn relative(
+        &self,
+        d: time::Date,
+        ll: crate::value::Location,
+    ) -> Result<(f64, f64, f64), Sgp4Error> {
+        const FLATTENING: f64 = 1.0 / 298.26;
+        let (x, y, z) = self.position(d)?;
+        let Some((lat, long, elev)) = ll else {
+            return Ok((x, y, z));
+        };
+        // Geodetic to geocentric, on the WGS-72 ellipsoid
+        let e2 = FLATTENING * (2.0 - FLATTENING);
+        let n = EARTH_RADIUS / (1.0 - e2 * lat.sin() * lat.sin()).sqrt();
+        let h = elev / 1000.0;
+        let lst = crate::sidereal::lst(d, long);
+        let (ox, oy, oz) = (
+            (n + h) * lat.cos() * lst.cos(),
+            (n + h) * lat.cos() * lst.sin(),
+            (n * (1.0 - e2) + h) * lat.sin(),
+        );
+        Ok((x - ox, y - oy, z - oz))
+    }
+
+    /// Equatorial coordinates as seen by the observer, or from the center of the earth without one
+    pub fn location(
+        &self,
+        d: time::Date,
+        ll: crate::value::Location,
+    ) -> Result<coord::Coord, Sgp4Error> {
+        let (x, y, z) = self.relative(d, ll)?;
+        Ok(coord::Coord::from_cartesian(x, y, z))
+    }
+
+    /// Distance from the observer, or the center of the earth without one, in AU
+    pub fn distance(&self, d: time::Date, ll: crate::value::Location) -> Result<f64, Sgp4Error> {
+        let (x, y, z) = self.relative(d, ll)?;
+        Ok((x * x + y * y + z * z).sqrt() / KM_PER_AU)
+    }
+
+    /// If the satellite is in sunlight, rather than the shadow of the earth
+    ///
+    /// The shadow is treated as a cylinder, which is accurate enough for low orbits
+    pub fn sunlit(&self, d: time::Date) -> Result<bool, Sgp4Error> {
+        let (x, y, z) = self.position(d)?;
+        let (ra, de) = sol::SUN.location(d).precess(time::J2000, d).equatorial();
+        let (sx, sy, sz) = (de.cos() * ra.cos(), de.cos() * ra.sin(), de.sin());
+        let along = x * sx + y * sy + z * sz;
+        let (px, py, pz) = (x - along * sx, y - along * sy, z - along * sz);
+        Ok(along > 0.0 || (px * px + py * py + pz * pz).sqrt() > EARTH_RADIUS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test case 00005 of Vallado et al. (2006), from its two-line elements:
+    /// ```text
+    /// 1 00005U 58002B   00179.78495062  .00000023  00000-0  28098-4 0  4753
+    /// 2 00005  34.2682 348.7242 1859667 331.7664  19.3264 10.82419157413667
+    /// ```
+    fn vanguard() -> Satellite {
+        let deg = time::Angle::from_degrees;
+        let new_year = time::Date::from_calendar(2000, 1, 1, time::Angle::default());
+        Satellite {
+            epoch: time::Date::from_julian(new_year.julian() + 179.784_950_62 - 1.0),
+            incl: deg(34.2682),
+            node: deg(348.7242),
+            ecc: 0.185_966_7,
+            argp: deg(331.7664),
+            mean_anomaly: deg(19.3264),
+            mean_motion: 10.824_191_57,
+            bstar: 0.280_98e-4,
+        }
+    }
+
+    #[test]
+    fn vallado_near_earth() {
+        let s = vanguard();
+        // Minutes from epoch, and the position in km given for it
+        let expected = [
+            (0.0, (7022.46529266, -1400.08296755, 0.03995155)),
+            (360.0, (-7154.03120202, -3783.17682504, -3536.19412294)),
+            (720.0, (-7134.59340119, 6531.68641334, 3260.27186483)),
+            (1080.0, (5568.53901181, 4492.06992591, 3863.87641983)),
+        ];
+        for (minutes, (x, y, z)) in expected {
+            let d = time::Date::from_julian(s.epoch.julian() + minutes / 1440.0);
+            let p = s.position(d).unwrap();
+            let off = ((p.0 - x).powi(2) + (p.1 - y).powi(2) + (p.2 - z).powi(2)).sqrt();
+            assert!(off < 0.01, "{off} km off at {minutes} minutes");
+        }
+    }
+
+    #[test]
+    fn deep_space_unsupported() {
+        let s = Satellite {
+            mean_motion: 1.0027,
+            ..vanguard()
+        };
+        assert_eq!(s.position(s.epoch), Err(Sgp4Error::DeepSpace));
+    }
+}
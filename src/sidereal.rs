@@ -0,0 +1,96 @@
+use pracstro::{coord, sol, time};
+
+/// Greenwich mean sidereal time (Meeus, Astronomical Algorithms, Eq. 12.4)
+///
+/// [`time::Angle::gst`] counts the time of day in its centuries as well as its hours, which puts
+/// it up to four minutes ahead by the end of the UT day.
+pub fn gmst(d: time::Date) -> time::Angle {
+    let days = d.julian() - time::J2000.julian();
+    let t = days / 36525.0;
+    time::Angle::from_degrees(
+        280.460_618_37 + 360.985_647_366_29 * days + 0.000_387_933 * t * t
+            - t * t * t / 38_710_000.0,
+    )
+}
+
+/// Local mean sidereal time at a longitude, east being positive
+pub fn lst(d: time::Date, long: time::Angle) -> time::Angle {
+    gmst(d) + long
+}
+
+/// The equation of time, apparent less mean solar time
+///
+/// From the hour angle of the sun at Greenwich, ignoring nutation, which is good to a couple of seconds.
+pub fn equation_of_time(d: time::Date) -> time::Angle {
+    let (ra, _) = sol::SUN.location(d).precess(time::J2000, d).equatorial();
+    let mean = d.time() - time::Angle::from_degrees(180.0);
+    gmst(d) - ra - mean
+}
+
+/// Azimuth (from the north, through the east) and altitude of a position at the date and place
+///
+/// The same as [`coord::Coord::horizon`], with the sidereal time from [`gmst`].
+pub fn horizon(
+    c: coord::Coord,
+    d: time::Date,
+    lat: time::Angle,
+    long: time::Angle,
+) -> (time::Angle, time::Angle) {
+    let (ra, de) = c.equatorial();
+    let ha = lst(d, long) - ra;
+    let alt = time::Angle::asin(de.sin() * lat.sin() + de.cos() * lat.cos() * ha.cos());
+    let azi = time::Angle::atan2(
+        -de.cos() * ha.sin(),
+        de.sin() * lat.cos() - de.cos() * lat.sin() * ha.cos(),
+    );
+    (azi, alt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Seconds of time in an angle
+    fn seconds(a: time::Angle) -> f64 {
+        a.degrees() * 240.0
+    }
+
+    #[test]
+    fn meeus_sidereal_time() {
+        // Examples 12.a and 12.b, 13h10m46.3668s at 0h and 8h34m57.0896s at 19h21m UT
+        // on 1987 April 10
+        let midnight = time::Date::from_calendar(1987, 4, 10, time::Angle::from_turns(0.0));
+        let evening = time::Date::from_calendar(1987, 4, 10, time::Angle::from_clock(19, 21, 0.0));
+        let expected = |h: f64, m: f64, s: f64| h * 3600.0 + m * 60.0 + s;
+        assert!((seconds(gmst(midnight)) - expected(13.0, 10.0, 46.3668)).abs() < 0.001);
+        assert!((seconds(gmst(evening)) - expected(8.0, 34.0, 57.0896)).abs() < 0.001);
+    }
+
+    #[test]
+    fn meeus_venus_horizon() {
+        // Example 13.b, Venus from the Naval Observatory in Washington,
+        // 68.0337° west of south and 15.1249° high
+        let d = time::Date::from_calendar(1987, 4, 10, time::Angle::from_clock(19, 21, 0.0));
+        let venus = coord::Coord::from_equatorial(
+            time::Angle::from_clock(23, 9, 16.641),
+            time::Angle::from_degrees(-(6.0 + 43.0 / 60.0 + 11.61 / 3600.0)),
+        );
+        let (azi, alt) = horizon(
+            venus,
+            d,
+            time::Angle::from_degrees(38.0 + 55.0 / 60.0 + 17.0 / 3600.0),
+            time::Angle::from_degrees(-(77.0 + 3.0 / 60.0 + 56.0 / 3600.0)),
+        );
+        // Meeus uses the apparent sidereal time, a quarter of a second behind the mean
+        assert!((azi.degrees() - (68.0337 + 180.0)).abs() < 0.002);
+        assert!((alt.degrees() - 15.1249).abs() < 0.002);
+    }
+
+    #[test]
+    fn meeus_equation_of_time() {
+        // Example 28.a, 13m42.7s on 1992 October 13, good to a couple of seconds without nutation
+        let d = time::Date::from_calendar(1992, 10, 13, time::Angle::from_turns(0.0));
+        let e = seconds(equation_of_time(d));
+        assert!((e - (13.0 * 60.0 + 42.7)).abs() < 3.0, "{e}");
+    }
+}
@@ -0,0 +1,58 @@
+use chrono::prelude::*;
+use pracstro::time;
+#[derive(Copy, Clone, Debug, PartialEq)]
+/// Most things can be represented as seconds or months
+/// * 1 second: 1 second
+/// * 1 minute: 60 seconds
+/// * 1 hour: 3600 seconds
+/// * 1 day: 86400 seconds
+/// * 1 week: 604800 seconds
+/// * 1 month: 1 month
+/// * 1 year: 12 months
+pub enum Step {
+    S(f64),
+    M(chrono::Months),
+}
+pub fn step_forward_date(d: time::Date, s: Step) -> time::Date {
+    match s {
+        Step::S(sec) => time::Date::from_julian(d.julian() + (sec.abs() / 86400.0)),
+        Step::M(m) => time::Date::from_unix(
+            (DateTime::from_timestamp(d.unix() as i64, 0).unwrap() + m).timestamp() as f64,
+        ),
+    }
+}
+pub fn step_back_date(d: time::Date, s: Step) -> time::Date {
+    match s {
+        Step::S(sec) => time::Date::from_julian(d.julian() - (sec.abs() / 86400.0)),
+        Step::M(m) => time::Date::from_unix(
+            (DateTime::from_timestamp(d.unix() as i64, 0).unwrap() - m).timestamp() as f64,
+        ),
+    }
+}
+pub struct EphemIter {
+    now: time::Date,
+    step: Step,
+    end: time::Date,
+}
+impl EphemIter {
+    pub fn new(start: time::Date, step: Step, end: time::Date) -> EphemIter {
+        EphemIter {
+            now: start,
+            step,
+            end,
+        }
+    }
+}
+impl Iterator for EphemIter {
+    type Item = time::Date;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.now.julian() < self.end.julian() {
+            let s = self.now;
+            self.now = step_forward_date(self.now, self.step);
+            Some(s)
+        } else {
+            None
+        }
+    }
+}
@@ -1,13 +1,59 @@
-use crate::catalog;
+use crate::{catalog, galactic, minor, moons, sat, sidereal};
 use pracstro::{coord, sol, time};
 use std::fmt;
 
-pub type Location = Option<(time::Angle, time::Angle)>;
+const KM_PER_AU: f64 = 149_597_870.7;
+
+/// Latitude, longitude, and elevation above sea level in meters of the observer
+pub type Location = Option<(time::Angle, time::Angle, f64)>;
+
+/// How the atmosphere bends light near the horizon
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Refraction {
+    /// Geometric altitudes, as if there was no atmosphere
+    None,
+    /// Sæmundsson's formula, for 10°C and 1010 hPa
+    Standard,
+    /// Sæmundsson's formula, corrected for temperature (°C) and pressure (hPa)
+    Atmosphere(f64, f64),
+}
+impl Refraction {
+    /// Scaling of the standard refraction for the conditions
+    fn factor(self) -> f64 {
+        match self {
+            Refraction::None => 0.0,
+            Refraction::Standard => 1.0,
+            Refraction::Atmosphere(t, p) => (p / 1010.0) * (283.0 / (273.0 + t)),
+        }
+    }
+
+    /// The apparent altitude of something at a geometric altitude
+    ///
+    /// Things more than a degree below the horizon are left alone
+    pub fn apparent(self, alt: time::Angle) -> time::Angle {
+        let h = alt.to_latitude().degrees();
+        if h < -1.0 {
+            return alt;
+        }
+        let r = 1.02 / (h + 10.3 / (h + 5.11)).to_radians().tan(); // Arcminutes
+        alt + time::Angle::from_degrees(self.factor() * r / 60.0)
+    }
+
+    /// Refraction at the horizon in degrees, for rise and set times
+    ///
+    /// This is the conventional 34′, rather than the formula, which is unreliable this low
+    pub fn horizon(self) -> f64 {
+        self.factor() * 34.0 / 60.0
+    }
+}
 
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub struct RefFrame {
     pub latlong: Location,
     pub date: time::Date,
+    pub refraction: Refraction,
+    /// The planet the sky is seen from, or the earth if `None`
+    pub from: Option<&'static sol::Planet>,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -23,6 +69,16 @@ pub enum CrdView {
     Equatorial,
     Horizontal(RefFrame),
     Ecliptic(time::Date),
+    /// The date is the equinox of the equatorial coordinates
+    Galactic(time::Date),
+    Supergalactic(time::Date),
+}
+
+/// The unit distances are shown in
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum DistUnit {
+    Au,
+    Km,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -31,7 +87,13 @@ pub enum CelObj {
     Moon,
     Sun,
     Star(catalog::Star),
+    Minor(minor::MinorBody),
+    Satellite(sat::Satellite),
+    DeepSky(catalog::DeepSky),
+    PlanetMoon(moons::PlanetMoon),
     Crd(coord::Coord),
+    /// The observer, rather than anything in the sky, for things like the sidereal time
+    Site,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -39,6 +101,10 @@ pub enum AngView {
     Angle,
     Latitude,
     Time,
+    /// Hours and minutes either side of zero, like the equation of time
+    SignedTime,
+    /// East of the sun when positive, and west when negative
+    Elongation,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -50,6 +116,18 @@ pub enum Value {
     Dist(f64),
     Phase(time::Angle, PhaseView),
     RsTime(Option<time::Date>),
+    /// A time that may be days away, shown with its date
+    Event(Option<time::Date>),
+    /// Offset on the sky in arcseconds, east and north
+    Offset(f64, f64),
+    /// The airmass, if the object is above the horizon
+    Airmass(Option<f64>),
+    /// A ratio without units, like the magnitude of an eclipse
+    Ratio(f64),
+    /// Rectangular coordinates in AU, and the unit to show them in
+    Xyz((f64, f64, f64), DistUnit),
+    Bool(bool),
+    Text(String),
 }
 
 impl fmt::Display for Value {
@@ -97,14 +175,28 @@ impl fmt::Display for Value {
                     write!(f, "{:02}°{:02}′{:02.1}″", d, m, s)
                 }
                 Value::Ang(p, AngView::Latitude) => {
-                    let (d, m, s) = p.to_latitude().degminsec();
-                    write!(f, "{:+02}°{:02}′{:02.1}″", d, m, s)
+                    let x = p.to_latitude().degrees();
+                    let sign = if x < 0.0 { '-' } else { '+' };
+                    let (d, m, s) = time::Angle::from_degrees(x.abs()).degminsec();
+                    write!(f, "{sign}{}°{:02}′{:02.1}″", d, m, s)
                 }
                 //Value::Per(p, AngView::Raw) => write!(f, "{:.5}", p.degrees()),
                 Value::Ang(p, AngView::Time) => {
                     let (h, m, s) = p.clock();
                     write!(f, "{:02}h{:02}m{:02}s", h, m, s.trunc())
                 }
+                Value::Ang(p, AngView::Elongation) => {
+                    let x = p.to_latitude().degrees();
+                    let side = if x < 0.0 { 'W' } else { 'E' };
+                    let (d, m, s) = time::Angle::from_degrees(x.abs()).degminsec();
+                    write!(f, "{:02}°{:02}′{:02.1}″ {side}", d, m, s)
+                }
+                Value::Ang(p, AngView::SignedTime) => {
+                    let x = p.to_latitude().degrees() / 15.0;
+                    let (h, m, s) = time::Angle::from_decimal(x.abs()).clock();
+                    let sign = if x < 0.0 { '-' } else { '+' };
+                    write!(f, "{sign}{:02}h{:02}m{:02}s", h, m, s.trunc())
+                }
                 Value::Dist(d) => match d {
                     0.0..0.003342293561 => write!(f, "{:.1} km", d * 149597870.7),
                     20000.0.. => write!(f, "{:.2} ly", d / 63241.07708),
@@ -120,13 +212,13 @@ impl fmt::Display for Value {
                     )
                 }
                 Value::Crd(c, CrdView::Horizontal(rf)) => {
-                    let (lat, long) = rf.latlong.unwrap();
-                    let d = c.horizon(rf.date, lat, long);
+                    let (lat, long, _) = rf.latlong.unwrap();
+                    let d = sidereal::horizon(*c, rf.date, lat, long);
                     write!(
                         f,
                         "{} {}",
                         Value::Ang(d.0, AngView::Angle),
-                        Value::Ang(d.1, AngView::Latitude)
+                        Value::Ang(rf.refraction.apparent(d.1), AngView::Latitude)
                     )
                 }
                 Value::Crd(c, CrdView::Ecliptic(d)) => {
@@ -138,6 +230,24 @@ impl fmt::Display for Value {
                         Value::Ang(d.1, AngView::Latitude)
                     )
                 }
+                Value::Crd(c, CrdView::Galactic(e)) => {
+                    let d = galactic::galactic(*c, *e);
+                    write!(
+                        f,
+                        "{} {}",
+                        Value::Ang(d.0, AngView::Angle),
+                        Value::Ang(d.1, AngView::Latitude)
+                    )
+                }
+                Value::Crd(c, CrdView::Supergalactic(e)) => {
+                    let d = galactic::supergalactic(*c, *e);
+                    write!(
+                        f,
+                        "{} {}",
+                        Value::Ang(d.0, AngView::Angle),
+                        Value::Ang(d.1, AngView::Latitude)
+                    )
+                }
                 Value::Phase(pa, PhaseView::Default(n)) => {
                     let ilf = (1.0 - pa.cos()) / 2.0;
                     let pi = phaseidx(ilf, *pa);
@@ -162,6 +272,19 @@ impl fmt::Display for Value {
                     write!(f, "{}", PNAMES[phaseidx((1.0 - pa.cos()) / 2.0, *pa)])
                 }
                 Value::Num(n) => write!(f, "{:0.2}", n),
+                Value::Bool(b) => write!(f, "{}", if *b { "yes" } else { "no" }),
+                Value::Text(s) => write!(f, "{s}"),
+                Value::Event(Some(d)) => write!(f, "{}", Value::Date(*d)),
+                Value::Event(None) => write!(f, "none"),
+                Value::Offset(e, n) => write!(f, "{e:+.1}″ {n:+.1}″"),
+                Value::Airmass(Some(x)) => write!(f, "{x:.3}"),
+                Value::Ratio(x) => write!(f, "{x:.4}"),
+                Value::Airmass(None) => write!(f, "none"),
+                Value::Xyz((x, y, z), DistUnit::Au) => write!(f, "{x:.6} {y:.6} {z:.6} AU"),
+                Value::Xyz(v, DistUnit::Km) => {
+                    let (x, y, z) = (v.0 * KM_PER_AU, v.1 * KM_PER_AU, v.2 * KM_PER_AU);
+                    write!(f, "{x:.1} {y:.1} {z:.1} km")
+                }
                 Value::RsTime(d) => {
                     if d.is_none() {
                         write!(f, "none")
@@ -197,6 +320,12 @@ impl fmt::Display for Value {
                 Value::Ang(p, AngView::Time) => {
                     write!(f, "{:.5}", p.decimal())
                 }
+                Value::Ang(p, AngView::SignedTime) => {
+                    write!(f, "{:.5}", p.to_latitude().degrees() / 15.0)
+                }
+                Value::Ang(p, AngView::Elongation) => {
+                    write!(f, "{:.5}", p.to_latitude().degrees())
+                }
                 Value::Dist(d) => write!(f, "{}", d),
                 Value::Crd(c, CrdView::Equatorial) => {
                     let d = c.equatorial();
@@ -208,13 +337,13 @@ impl fmt::Display for Value {
                     )
                 }
                 Value::Crd(c, CrdView::Horizontal(rf)) => {
-                    let (lat, long) = rf.latlong.unwrap();
-                    let d = c.horizon(rf.date, lat, long);
+                    let (lat, long, _) = rf.latlong.unwrap();
+                    let d = sidereal::horizon(*c, rf.date, lat, long);
                     write!(
                         f,
                         "[{:#}, {:#}]",
                         Value::Ang(d.0, AngView::Angle),
-                        Value::Ang(d.1.refract(), AngView::Latitude)
+                        Value::Ang(rf.refraction.apparent(d.1), AngView::Latitude)
                     )
                 }
                 Value::Crd(c, CrdView::Ecliptic(d)) => {
@@ -226,6 +355,24 @@ impl fmt::Display for Value {
                         Value::Ang(d.1, AngView::Latitude)
                     )
                 }
+                Value::Crd(c, CrdView::Galactic(e)) => {
+                    let d = galactic::galactic(*c, *e);
+                    write!(
+                        f,
+                        "[{:#}, {:#}]",
+                        Value::Ang(d.0, AngView::Angle),
+                        Value::Ang(d.1, AngView::Latitude)
+                    )
+                }
+                Value::Crd(c, CrdView::Supergalactic(e)) => {
+                    let d = galactic::supergalactic(*c, *e);
+                    write!(
+                        f,
+                        "[{:#}, {:#}]",
+                        Value::Ang(d.0, AngView::Angle),
+                        Value::Ang(d.1, AngView::Latitude)
+                    )
+                }
                 Value::Phase(pa, PhaseView::Default(h)) => {
                     let ilf = (1.0 - pa.cos()) / 2.0;
                     let pi = phaseidx(ilf, *pa);
@@ -250,7 +397,66 @@ impl fmt::Display for Value {
                     write!(f, "\"{}\"", PNAMES[phaseidx((1.0 - pa.cos()) / 2.0, *pa)])
                 }
                 Value::Num(n) => write!(f, "{:0.2}", n),
+                Value::Bool(b) => write!(f, "{}", b),
+                Value::Text(s) => write!(f, "{s:?}"),
+                Value::Event(d) => write!(f, "{:#}", Value::RsTime(*d)),
+                Value::Offset(e, n) => write!(f, "[{e:.2}, {n:.2}]"),
+                Value::Airmass(Some(x)) => write!(f, "{x}"),
+                Value::Ratio(x) => write!(f, "{x}"),
+                // Below the horizon
+                Value::Airmass(None) => write!(f, "null"),
+                Value::Xyz((x, y, z), DistUnit::Au) => write!(f, "[{x}, {y}, {z}]"),
+                Value::Xyz(v, DistUnit::Km) => write!(
+                    f,
+                    "[{}, {}, {}]",
+                    v.0 * KM_PER_AU,
+                    v.1 * KM_PER_AU,
+                    v.2 * KM_PER_AU
+                ),
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Refraction in arcminutes of something seen at `apparent` degrees, found by bisection
+    fn refraction(r: Refraction, apparent: f64) -> f64 {
+        let (mut lo, mut hi) = (apparent - 1.0, apparent);
+        for _ in 0..50 {
+            let mid = (lo + hi) / 2.0;
+            match r
+                .apparent(time::Angle::from_degrees(mid))
+                .to_latitude()
+                .degrees()
+                < apparent
+            {
+                true => lo = mid,
+                false => hi = mid,
+            }
+        }
+        (apparent - lo) * 60.0
+    }
+
+    #[test]
+    fn refraction_tables() {
+        // Bennett's formula for what's seen at an altitude (Meeus, Eq. 16.4),
+        // 34.5′ at the horizon and 5.39′ at 10°
+        assert!((refraction(Refraction::Standard, 0.0) - 34.5).abs() < 0.1);
+        assert!((refraction(Refraction::Standard, 10.0) - 5.39).abs() < 0.1);
+        assert!((Refraction::Standard.horizon() - 34.0 / 60.0).abs() < 1e-9);
+        // The standard conditions are 10°C and 1010 hPa, and it's all gone without an atmosphere
+        let standard = Refraction::Atmosphere(10.0, 1010.0);
+        assert!((refraction(standard, 10.0) - refraction(Refraction::Standard, 10.0)).abs() < 1e-6);
+        assert!(refraction(Refraction::None, 10.0) < 1e-6);
+    }
+
+    #[test]
+    fn airmass_below_the_horizon() {
+        assert_eq!(Value::Airmass(None).to_string(), "none");
+        assert_eq!(format!("{:#}", Value::Airmass(None)), "null");
+        assert_eq!(format!("{:#}", Value::Airmass(Some(1.5))), "1.5");
+    }
+}